@@ -0,0 +1,12 @@
+#![no_main]
+
+use a3_preset_parser::Preset;
+use libfuzzer_sys::fuzz_target;
+
+// Parsing arbitrary text must only ever succeed or fail, never panic.
+fuzz_target!(|document_text: &str| {
+  let _ = document_text.parse::<Preset>();
+  let _ = Preset::parse_lenient(document_text);
+  let _ = Preset::parse_best_effort(document_text);
+  let _ = Preset::parse_streaming(document_text);
+});
@@ -0,0 +1,94 @@
+use crate::{AppId, Game, Preset, PresetDlc, PresetKind, PresetLocalMod, PresetSteamMod, WorkshopId};
+
+use std::borrow::Cow;
+
+/// A [`Preset`] borrowing its names from a parsed [`Html`][crate::Html] document,
+/// see [`Preset::parse_borrowed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresetRef<'a> {
+  pub game: Game,
+  pub kind: PresetKind,
+  pub preset_name: Option<&'a str>,
+  pub steam_mods: Vec<PresetSteamModRef<'a>>,
+  pub local_mods: Vec<PresetLocalModRef<'a>>,
+  pub dlcs: Vec<PresetDlcRef<'a>>,
+  pub extra_meta: Vec<(&'a str, &'a str)>,
+  pub generated_by: Option<&'a str>,
+  pub generated_at: Option<&'a str>
+}
+
+impl PresetRef<'_> {
+  /// Copies all borrowed names into a new [`Preset`].
+  pub fn to_owned(&self) -> Preset {
+    Preset::from(self.clone())
+  }
+}
+
+impl From<PresetRef<'_>> for Preset {
+  fn from(preset: PresetRef<'_>) -> Self {
+    Preset {
+      game: preset.game,
+      kind: preset.kind,
+      preset_name: preset.preset_name.map(str::to_owned),
+      steam_mods: preset.steam_mods.into_iter().map(PresetSteamMod::from).collect(),
+      local_mods: preset.local_mods.into_iter().map(PresetLocalMod::from).collect(),
+      dlcs: preset.dlcs.into_iter().map(PresetDlc::from).collect(),
+      extra_meta: preset.extra_meta.into_iter().map(|(name, content)| (name.to_owned(), content.to_owned())).collect(),
+      generated_by: preset.generated_by.map(str::to_owned),
+      generated_at: preset.generated_at.map(str::to_owned)
+    }
+  }
+}
+
+/// A [`PresetSteamMod`] with a borrowed display name.
+///
+/// The name is only owned when it had to be joined from multiple text nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresetSteamModRef<'a> {
+  pub display_name: Cow<'a, str>,
+  pub id: WorkshopId,
+  pub order: usize
+}
+
+impl From<PresetSteamModRef<'_>> for PresetSteamMod {
+  fn from(steam_mod: PresetSteamModRef<'_>) -> Self {
+    PresetSteamMod {
+      display_name: steam_mod.display_name.into_owned(),
+      id: steam_mod.id,
+      order: Some(steam_mod.order)
+    }
+  }
+}
+
+/// A [`PresetLocalMod`] with a borrowed display name.
+///
+/// The name is only owned when it had to be joined from multiple text nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresetLocalModRef<'a> {
+  pub display_name: Cow<'a, str>,
+  pub order: usize
+}
+
+impl From<PresetLocalModRef<'_>> for PresetLocalMod {
+  fn from(local_mod: PresetLocalModRef<'_>) -> Self {
+    PresetLocalMod {
+      display_name: local_mod.display_name.into_owned(),
+      order: Some(local_mod.order)
+    }
+  }
+}
+
+/// A [`PresetDlc`] with a borrowed display name.
+///
+/// The name is only owned when it had to be joined from multiple text nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresetDlcRef<'a> {
+  pub display_name: Cow<'a, str>,
+  pub id: AppId
+}
+
+impl From<PresetDlcRef<'_>> for PresetDlc {
+  fn from(dlc: PresetDlcRef<'_>) -> Self {
+    PresetDlc { display_name: dlc.display_name.into_owned(), id: dlc.id }
+  }
+}
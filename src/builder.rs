@@ -0,0 +1,114 @@
+use crate::{AppId, Error, Game, NameResolution, Preset, PresetKind, WorkshopId};
+
+use std::collections::{HashMap, HashSet};
+
+/// A builder for assembling a [`Preset`] programmatically.
+///
+/// Items are kept in the order they were added, duplicates are not removed,
+/// see [`Preset::dedupe_all`] if that is needed.
+#[derive(Debug, Clone)]
+pub struct PresetBuilder {
+  preset: Preset
+}
+
+impl PresetBuilder {
+  /// Starts a new preset of kind [`PresetKind::Preset`] with no name and no items.
+  pub fn new(game: Game) -> Self {
+    PresetBuilder { preset: Preset::new(game) }
+  }
+
+  pub fn kind(mut self, kind: PresetKind) -> Self {
+    self.preset.kind = kind;
+    self
+  }
+
+  pub fn name(mut self, preset_name: impl Into<String>) -> Self {
+    self.preset.preset_name = Some(preset_name.into());
+    self
+  }
+
+  pub fn steam_mod(mut self, id: impl Into<WorkshopId>, display_name: impl Into<String>) -> Self {
+    self.preset.add_steam_mod(id, display_name);
+    self
+  }
+
+  pub fn local_mod(mut self, display_name: impl Into<String>) -> Self {
+    self.preset.add_local_mod(display_name);
+    self
+  }
+
+  pub fn dlc(mut self, id: impl Into<AppId>, display_name: impl Into<String>) -> Self {
+    self.preset.add_dlc(id, display_name);
+    self
+  }
+
+  pub fn build(self) -> Preset {
+    self.preset
+  }
+}
+
+/// A builder for merging presets from several labelled sources, such as the files they were read from,
+/// while recording which sources each Steam mod and DLC came from.
+///
+/// Entries are merged in the order they were added, with the same rules as [`Preset::merge_with`].
+#[derive(Debug, Clone)]
+pub struct MergeBuilder {
+  game: Game,
+  resolution: NameResolution,
+  entries: Vec<(String, Preset)>
+}
+
+impl MergeBuilder {
+  /// Starts a merge into an empty preset for `game`, see [`Preset::new`].
+  pub fn new(game: Game) -> Self {
+    MergeBuilder { game, resolution: NameResolution::default(), entries: Vec::new() }
+  }
+
+  /// Which display name to keep for items present in more than one entry.
+  /// Defaults to [`NameResolution::FirstSeen`].
+  pub fn resolution(mut self, resolution: NameResolution) -> Self {
+    self.resolution = resolution;
+    self
+  }
+
+  pub fn entry(mut self, label: impl Into<String>, preset: Preset) -> Self {
+    self.entries.push((label.into(), preset));
+    self
+  }
+
+  pub fn entries<L, I>(mut self, entries: I) -> Self
+  where L: Into<String>, I: IntoIterator<Item = (L, Preset)> {
+    self.entries.extend(entries.into_iter().map(|(label, preset)| (label.into(), preset)));
+    self
+  }
+
+  /// Merges the entries, returning the merged preset along with the labels of the entries
+  /// containing each Steam mod and DLC, see [`MergeProvenance`].
+  ///
+  /// Fails with [`Error::GameMismatch`] if any entry is not for the builder's game.
+  pub fn build(self) -> Result<(Preset, MergeProvenance), Error> {
+    let mut preset = Preset::new(self.game);
+    let mut provenance = MergeProvenance::default();
+    for (label, other) in self.entries {
+      preset.merge_with(&other, self.resolution)?;
+
+      for id in other.steam_mods.iter().map(|m| m.id).collect::<HashSet<WorkshopId>>() {
+        provenance.steam_mods.entry(id).or_default().push(label.clone());
+      };
+
+      for id in other.dlcs.iter().map(|m| m.id).collect::<HashSet<AppId>>() {
+        provenance.dlcs.entry(id).or_default().push(label.clone());
+      };
+    };
+
+    Ok((preset, provenance))
+  }
+}
+
+/// The labels of the [`MergeBuilder`] entries containing each Steam mod and DLC of a merged preset,
+/// in the order the entries were added.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeProvenance {
+  pub steam_mods: HashMap<WorkshopId, Vec<String>>,
+  pub dlcs: HashMap<AppId, Vec<String>>
+}
@@ -0,0 +1,220 @@
+use crate::{AppId, Error, NameNormalization, Preset, PresetDlc, PresetLocalMod, PresetSteamMod, WorkshopId};
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// The result of comparing two presets, `a` and `b`, as sets.
+///
+/// Steam mods and DLCs are matched by id, local mods by normalized display name.
+/// Items shared by both presets are taken from `a`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresetDiff<'p> {
+  pub steam_only_a: Vec<&'p PresetSteamMod>,
+  pub steam_only_b: Vec<&'p PresetSteamMod>,
+  pub steam_shared: Vec<&'p PresetSteamMod>,
+  pub dlc_only_a: Vec<&'p PresetDlc>,
+  pub dlc_only_b: Vec<&'p PresetDlc>,
+  pub dlc_shared: Vec<&'p PresetDlc>,
+  pub local_only_a: Vec<&'p PresetLocalMod>,
+  pub local_only_b: Vec<&'p PresetLocalMod>,
+  pub local_shared: Vec<&'p PresetLocalMod>
+}
+
+impl PresetDiff<'_> {
+  /// Returns true if neither preset has any item the other is missing.
+  pub fn is_empty(&self) -> bool {
+    self.steam_only_a.is_empty() && self.steam_only_b.is_empty() &&
+    self.dlc_only_a.is_empty() && self.dlc_only_b.is_empty() &&
+    self.local_only_a.is_empty() && self.local_only_b.is_empty()
+  }
+}
+
+/// Options for [`Preset::diff_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DiffOptions {
+  /// Pair a local mod in one preset with a Steam mod in the other when their display names match,
+  /// so that a mod installed locally on one side and subscribed to on the other counts as shared.
+  ///
+  /// Names are matched by their normalized form, as for matching local mods.
+  pub match_local_to_steam_by_name: bool,
+  /// How display names are normalized for matching local mods, see [`NameNormalization`].
+  pub normalization: NameNormalization
+}
+
+impl Preset {
+  /// Compares the contents of this preset (`a`) against `other` (`b`).
+  ///
+  /// Fails with [`Error::GameMismatch`] if the presets are not for the same game.
+  pub fn diff<'p>(&'p self, other: &'p Preset) -> Result<PresetDiff<'p>, Error> {
+    self.diff_with_options(other, DiffOptions::default())
+  }
+
+  /// Compares the contents of this preset (`a`) against `other` (`b`), as configured by `options`.
+  ///
+  /// With [`DiffOptions::match_local_to_steam_by_name`], a matched item from `a` is listed
+  /// as shared with the other items of its own kind, and the item it matched in `b` is omitted.
+  /// Fails with [`Error::GameMismatch`] if the presets are not for the same game.
+  pub fn diff_with_options<'p>(&'p self, other: &'p Preset, options: DiffOptions) -> Result<PresetDiff<'p>, Error> {
+    self.assert_same_game(other)?;
+
+    let normalize = |name: &str| options.normalization.normalize(name);
+    let steam_ids_a = self.steam_mods.iter().map(|m| m.id).collect::<HashSet<WorkshopId>>();
+    let steam_ids_b = other.steam_mods.iter().map(|m| m.id).collect::<HashSet<WorkshopId>>();
+    let dlc_ids_a = self.dlcs.iter().map(|m| m.id).collect::<HashSet<AppId>>();
+    let dlc_ids_b = other.dlcs.iter().map(|m| m.id).collect::<HashSet<AppId>>();
+    let local_names_a = self.local_mods.iter().map(|m| normalize(&m.display_name)).collect::<HashSet<String>>();
+    let local_names_b = other.local_mods.iter().map(|m| normalize(&m.display_name)).collect::<HashSet<String>>();
+    let (steam_names_a, steam_names_b) = if options.match_local_to_steam_by_name {
+      (
+        self.steam_mods.iter().map(|m| normalize(&m.display_name)).collect::<HashSet<String>>(),
+        other.steam_mods.iter().map(|m| normalize(&m.display_name)).collect::<HashSet<String>>()
+      )
+    } else {
+      (HashSet::new(), HashSet::new())
+    };
+
+    let matches_local = |m: &PresetSteamMod, local_names: &HashSet<String>| {
+      options.match_local_to_steam_by_name && local_names.contains(&normalize(&m.display_name))
+    };
+
+    let (steam_shared, steam_only_a) = self.steam_mods.iter()
+      .partition(|m| steam_ids_b.contains(&m.id) || matches_local(m, &local_names_b));
+    let steam_only_b = other.steam_mods.iter()
+      .filter(|m| !steam_ids_a.contains(&m.id) && !matches_local(m, &local_names_a)).collect();
+    let (dlc_shared, dlc_only_a) = self.dlcs.iter()
+      .partition(|m| dlc_ids_b.contains(&m.id));
+    let dlc_only_b = other.dlcs.iter()
+      .filter(|m| !dlc_ids_a.contains(&m.id)).collect();
+    let (local_shared, local_only_a) = self.local_mods.iter()
+      .partition(|m| {
+        let name = normalize(&m.display_name);
+        local_names_b.contains(&name) || steam_names_b.contains(&name)
+      });
+    let local_only_b = other.local_mods.iter()
+      .filter(|m| {
+        let name = normalize(&m.display_name);
+        !local_names_a.contains(&name) && !steam_names_a.contains(&name)
+      })
+      .collect();
+
+    Ok(PresetDiff {
+      steam_only_a,
+      steam_only_b,
+      steam_shared,
+      dlc_only_a,
+      dlc_only_b,
+      dlc_shared,
+      local_only_a,
+      local_only_b,
+      local_shared
+    })
+  }
+
+  /// Compares two presets by content, ignoring order and `preset_name`.
+  ///
+  /// Steam mods and DLCs are compared as sets of ids, local mods as a multiset of normalized names.
+  pub fn eq_unordered(&self, other: &Preset) -> bool {
+    self.eq_unordered_with(other, NameNormalization::default())
+  }
+
+  /// Like [`Preset::eq_unordered`], but with local mod names compared as normalized by `normalization`.
+  pub fn eq_unordered_with(&self, other: &Preset, normalization: NameNormalization) -> bool {
+    self.game == other.game &&
+    self.unordered_content(normalization) == other.unordered_content(normalization)
+  }
+
+  /// Returns true if `other` is for the same game and contains every item in this preset,
+  /// with items matched as in [`Preset::diff`]. Every preset is a subset of itself.
+  pub fn is_subset_of(&self, other: &Preset) -> bool {
+    self.diff(other).is_ok_and(|diff| {
+      diff.steam_only_a.is_empty() && diff.dlc_only_a.is_empty() && diff.local_only_a.is_empty()
+    })
+  }
+
+  /// Returns the ids from `required` that are neither a Steam mod nor a DLC of this preset,
+  /// in the order they appear in `required`, for checking a preset against a list of required mods.
+  ///
+  /// A required list may mix workshop ids and app ids, so ids are taken as plain numbers and
+  /// matched against both. Use [`Preset::missing_dlcs_from`] to check only DLCs.
+  pub fn missing_from<I>(&self, required: I) -> Vec<u64>
+  where I: IntoIterator, I::Item: Into<u64> {
+    let ids = self.steam_mods.iter().map(|m| m.id.0)
+      .chain(self.dlcs.iter().map(|m| m.id.0))
+      .collect::<HashSet<u64>>();
+    required.into_iter().map(Into::into).filter(|id| !ids.contains(id)).collect()
+  }
+
+  /// Returns the app ids from `required` that are not a DLC of this preset,
+  /// in the order they appear in `required`. Steam mods are not checked.
+  pub fn missing_dlcs_from<I>(&self, required: I) -> Vec<AppId>
+  where I: IntoIterator, I::Item: Into<AppId> {
+    let ids = self.dlcs.iter().map(|m| m.id).collect::<HashSet<AppId>>();
+    required.into_iter().map(Into::into).filter(|id| !ids.contains(id)).collect()
+  }
+
+  /// Returns the Steam mods of this preset whose id is not in `allowed`, in preset order,
+  /// the reverse of [`Preset::missing_from`]. Local mods and DLCs are not checked.
+  pub fn extra_beyond<I>(&self, allowed: I) -> Vec<&PresetSteamMod>
+  where I: IntoIterator, I::Item: Into<WorkshopId> {
+    let allowed = allowed.into_iter().map(Into::into).collect::<HashSet<WorkshopId>>();
+    self.steam_mods.iter().filter(|m| !allowed.contains(&m.id)).collect()
+  }
+
+  /// A hash of this preset's content that is consistent with [`Preset::eq_unordered`].
+  ///
+  /// Uses the standard library's [`DefaultHasher`], it is not stable across Rust versions.
+  pub fn content_hash(&self) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    self.game.hash(&mut hasher);
+    self.unordered_content(NameNormalization::default()).hash(&mut hasher);
+    hasher.finish()
+  }
+
+  /// A SHA-256 fingerprint of this preset's content, consistent with [`Preset::eq_unordered`].
+  ///
+  /// Unlike [`Preset::content_hash`], this is stable, covering the game, the sorted Steam mod
+  /// and DLC ids, and the sorted normalized local mod names. It excludes `preset_name` and `kind`,
+  /// so two files differing only in formatting, order or name produce the same fingerprint.
+  #[cfg(feature = "sha2")]
+  pub fn fingerprint(&self) -> [u8; 32] {
+    self.fingerprint_with(NameNormalization::default())
+  }
+
+  /// Like [`Preset::fingerprint`], but with local mod names normalized by `normalization`,
+  /// consistent with [`Preset::eq_unordered_with`].
+  #[cfg(feature = "sha2")]
+  pub fn fingerprint_with(&self, normalization: NameNormalization) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+
+    let (steam_ids, dlc_ids, local_names) = self.unordered_content(normalization);
+    let mut hasher = Sha256::new();
+    hasher.update(self.game.meta_namespace().as_bytes());
+    let steam_ids = steam_ids.into_iter().map(u64::from).collect::<Vec<u64>>();
+    let dlc_ids = dlc_ids.into_iter().map(u64::from).collect::<Vec<u64>>();
+    for ids in [&steam_ids, &dlc_ids] {
+      hasher.update((ids.len() as u64).to_le_bytes());
+      for id in ids {
+        hasher.update(id.to_le_bytes());
+      };
+    };
+
+    hasher.update((local_names.len() as u64).to_le_bytes());
+    for name in local_names.iter() {
+      hasher.update((name.len() as u64).to_le_bytes());
+      hasher.update(name.as_bytes());
+    };
+
+    hasher.finalize().into()
+  }
+
+  fn unordered_content(&self, normalization: NameNormalization) -> (BTreeSet<WorkshopId>, BTreeSet<AppId>, Vec<String>) {
+    let steam_ids = self.steam_mods.iter().map(|m| m.id).collect();
+    let dlc_ids = self.dlcs.iter().map(|m| m.id).collect();
+    let mut local_names = self.local_mods.iter()
+      .map(|m| normalization.normalize(&m.display_name))
+      .collect::<Vec<String>>();
+    local_names.sort_unstable();
+    (steam_ids, dlc_ids, local_names)
+  }
+}
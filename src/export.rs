@@ -0,0 +1,320 @@
+use crate::{AppId, Error, ItemId, ModNameMap, ModRef, Origin, Preset};
+#[cfg(feature = "serde")]
+use crate::WorkshopId;
+
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+/// A format a preset can be rendered as, with [`Preset::render`].
+///
+/// Parses from its lowercase name, ignoring case, e.g. `json`, so it can be taken directly from command line arguments.
+///
+/// Non-exhaustive, since [`OutputFormat::Json`] is only available with the `serde` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OutputFormat {
+  /// The [`Display`][fmt::Display] text of the preset, accepts `text` or `txt`.
+  Text,
+  /// See [`Preset::to_html`], accepts `html`.
+  Html,
+  /// See [`Preset::to_json`], accepts `json`.
+  #[cfg(feature = "serde")]
+  Json,
+  /// See [`Preset::to_csv`], accepts `csv`.
+  Csv,
+  /// See [`Preset::to_markdown`], accepts `markdown` or `md`.
+  Markdown
+}
+
+impl OutputFormat {
+  /// The usual file extension of this format, without a leading `.`.
+  pub fn extension(self) -> &'static str {
+    match self {
+      OutputFormat::Text => "txt",
+      OutputFormat::Html => "html",
+      #[cfg(feature = "serde")]
+      OutputFormat::Json => "json",
+      OutputFormat::Csv => "csv",
+      OutputFormat::Markdown => "md"
+    }
+  }
+}
+
+impl fmt::Display for OutputFormat {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(match self {
+      OutputFormat::Text => "text",
+      OutputFormat::Html => "html",
+      #[cfg(feature = "serde")]
+      OutputFormat::Json => "json",
+      OutputFormat::Csv => "csv",
+      OutputFormat::Markdown => "markdown"
+    })
+  }
+}
+
+impl FromStr for OutputFormat {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.trim().to_ascii_lowercase().as_str() {
+      "text" | "txt" => Ok(OutputFormat::Text),
+      "html" => Ok(OutputFormat::Html),
+      #[cfg(feature = "serde")]
+      "json" => Ok(OutputFormat::Json),
+      "csv" => Ok(OutputFormat::Csv),
+      "markdown" | "md" => Ok(OutputFormat::Markdown),
+      _ => Err(Error::InvalidOutputFormat(s.to_owned()))
+    }
+  }
+}
+
+impl Preset {
+  /// Renders this preset in the given format.
+  pub fn render(&self, format: OutputFormat) -> String {
+    match format {
+      OutputFormat::Text => self.to_string(),
+      OutputFormat::Html => self.to_html(),
+      #[cfg(feature = "serde")]
+      OutputFormat::Json => self.to_json(),
+      OutputFormat::Csv => self.to_csv(),
+      OutputFormat::Markdown => self.to_markdown()
+    }
+  }
+
+  /// Exports the items of this preset as CSV, one row per item.
+  ///
+  /// The first row is always the header `kind,id,display_name`, where `kind` is one of
+  /// `steam`, `local` or `dlc`, and `id` is empty for local mods. Steam mods come first,
+  /// followed by local mods and then DLCs. Fields containing commas, quotes or line breaks
+  /// are quoted, with quotes doubled.
+  pub fn to_csv(&self) -> String {
+    let mut out = String::from("kind,id,display_name\n");
+    for (origin, display_name, id) in self.rows() {
+      match id {
+        Some(id) => writeln!(out, "{origin},{id},{}", CsvField(display_name)).unwrap(),
+        None => writeln!(out, "{origin},,{}", CsvField(display_name)).unwrap()
+      };
+    };
+
+    out
+  }
+
+  /// Exports this preset as a Markdown document, with the preset name as a heading
+  /// followed by a table of all items with `Name`, `Source` and `Link` columns.
+  ///
+  /// Local mods have an empty `Link` column. Markdown syntax in names is escaped.
+  pub fn to_markdown(&self) -> String {
+    let mut out = String::new();
+    match self.preset_name.as_deref() {
+      Some(preset_name) => writeln!(out, "# {}\n", MarkdownText(preset_name)).unwrap(),
+      None => writeln!(out, "# {} {}\n", self.game, self.kind).unwrap()
+    };
+
+    out.push_str("| Name | Source | Link |\n");
+    out.push_str("| --- | --- | --- |\n");
+    for (origin, display_name, id) in self.rows() {
+      let source = match origin {
+        Origin::Steam => "Steam",
+        Origin::Local => "Local",
+        Origin::Dlc => "DLC"
+      };
+
+      match id.map(ItemId::url) {
+        Some(url) => writeln!(out, "| {} | {source} | [{url}]({url}) |", MarkdownText(display_name)).unwrap(),
+        None => writeln!(out, "| {} | {source} | |", MarkdownText(display_name)).unwrap()
+      };
+    };
+
+    out
+  }
+
+  /// Exports the mods of this preset as a GitHub-flavored Markdown task list, for tracking installs,
+  /// with a `- [ ] Name (link)` line for each Steam mod followed by a `- [ ] Name` line for each local mod.
+  ///
+  /// DLCs are omitted. Markdown syntax in names is escaped.
+  pub fn to_task_list(&self) -> String {
+    let mut out = String::new();
+    for (_, display_name, id) in self.rows().filter(|&(origin, ..)| origin != Origin::Dlc) {
+      match id.map(ItemId::url) {
+        Some(url) => writeln!(out, "- [ ] {} ({url})", MarkdownText(display_name)).unwrap(),
+        None => writeln!(out, "- [ ] {}", MarkdownText(display_name)).unwrap()
+      };
+    };
+
+    out
+  }
+
+  /// Builds an Arma `-mod=` launch parameter from the mods of this preset, e.g. `-mod=@450814997;@MyMod`.
+  ///
+  /// Steam mods map to their `@id` workshop directory. Local mods map to a folder-safe version
+  /// of their display name, as in [`ModNameMap::folder_name`], so that no name can contain a `;`.
+  /// See [`Preset::to_mod_param_mapped`] or [`Preset::to_mod_param_with`] to override this.
+  /// When `base_dir` is provided, it is prepended to each folder, joined with `\` if `base_dir`
+  /// only uses backslashes as in a Windows path, and with `/` otherwise.
+  /// The result is not quoted for use in a shell.
+  pub fn to_mod_param(&self, base_dir: Option<&str>) -> String {
+    self.to_mod_param_mapped(base_dir, &ModNameMap::new())
+  }
+
+  /// Like [`Preset::to_mod_param`], but with each mod's folder name looked up in `names`.
+  pub fn to_mod_param_mapped(&self, base_dir: Option<&str>, names: &ModNameMap) -> String {
+    self.to_mod_param_with(base_dir, |m| names.folder_name(m))
+  }
+
+  /// Like [`Preset::to_mod_param`], but with each mod's folder name supplied by `folder_name`.
+  pub fn to_mod_param_with<F>(&self, base_dir: Option<&str>, mut folder_name: F) -> String
+  where F: FnMut(ModRef<'_>) -> String {
+    let base_dir = base_dir.map(|base_dir| {
+      let separator = match base_dir.contains('\\') && !base_dir.contains('/') {
+        true => '\\',
+        false => '/'
+      };
+      (base_dir.trim_end_matches(['/', '\\']), separator)
+    });
+    let mut out = String::from("-mod=");
+    for (i, m) in self.all_mods().enumerate() {
+      if i != 0 {
+        out.push(';');
+      };
+
+      if let Some((base_dir, separator)) = base_dir {
+        out.push_str(base_dir);
+        out.push(separator);
+      };
+
+      out.push_str(&folder_name(m));
+    };
+
+    out
+  }
+
+  /// Builds an Arma3Sync modset entry, consisting of a `[name]` header line followed by one
+  /// addon folder per line, in the order of [`Preset::all_mods`].
+  ///
+  /// Steam mods map to their `@id` workshop directory. Local mods map to a folder-safe version
+  /// of their display name, as in [`ModNameMap::folder_name`].
+  /// See [`Preset::to_arma3sync_modset_mapped`] or [`Preset::to_arma3sync_modset_with`] to override this.
+  /// Since the header cannot be escaped, any `]` or control character such as a line break in `name`
+  /// is replaced with `_`, so that it cannot end the header early.
+  pub fn to_arma3sync_modset(&self, name: &str) -> String {
+    self.to_arma3sync_modset_mapped(name, &ModNameMap::new())
+  }
+
+  /// Like [`Preset::to_arma3sync_modset`], but with each mod's folder name looked up in `names`.
+  pub fn to_arma3sync_modset_mapped(&self, name: &str, names: &ModNameMap) -> String {
+    self.to_arma3sync_modset_with(name, |m| names.folder_name(m))
+  }
+
+  /// Like [`Preset::to_arma3sync_modset`], but with each mod's folder name supplied by `folder_name`.
+  pub fn to_arma3sync_modset_with<F>(&self, name: &str, mut folder_name: F) -> String
+  where F: FnMut(ModRef<'_>) -> String {
+    let name = name.replace(|c: char| c == ']' || c.is_control(), "_");
+    let mut out = format!("[{name}]\n");
+    for m in self.all_mods() {
+      out.push_str(&folder_name(m));
+      out.push('\n');
+    };
+
+    out
+  }
+
+  /// Builds a server config fragment listing the mods of this preset, for hosting panels that read
+  /// mods from a config file rather than a `-mod=` parameter, in the order of [`Preset::all_mods`]:
+  ///
+  /// ```text
+  /// class ModList
+  /// {
+  ///     mods[] =
+  ///     {
+  ///         "@450814997",
+  ///         "@MyMod"
+  ///     };
+  /// };
+  /// ```
+  ///
+  /// Each level is indented with a tab, and any `"` in a folder name is doubled as in config strings.
+  /// Steam mods map to their `@id` workshop directory. Local mods map to a folder-safe version
+  /// of their display name, as in [`ModNameMap::folder_name`].
+  /// See [`Preset::to_server_modlist_cfg_mapped`] or [`Preset::to_server_modlist_cfg_with`] to override this.
+  pub fn to_server_modlist_cfg(&self) -> String {
+    self.to_server_modlist_cfg_mapped(&ModNameMap::new())
+  }
+
+  /// Like [`Preset::to_server_modlist_cfg`], but with each mod's folder name looked up in `names`.
+  pub fn to_server_modlist_cfg_mapped(&self, names: &ModNameMap) -> String {
+    self.to_server_modlist_cfg_with(|m| names.folder_name(m))
+  }
+
+  /// Like [`Preset::to_server_modlist_cfg`], but with each mod's folder name supplied by `folder_name`.
+  pub fn to_server_modlist_cfg_with<F>(&self, mut folder_name: F) -> String
+  where F: FnMut(ModRef<'_>) -> String {
+    let mut out = String::from("class ModList\n{\n\tmods[] =\n\t{\n");
+    let mut mods = self.all_mods().peekable();
+    while let Some(m) = mods.next() {
+      let separator = if mods.peek().is_some() { "," } else { "" };
+      writeln!(out, "\t\t\"{}\"{separator}", folder_name(m).replace('"', "\"\"")).unwrap();
+    };
+
+    out.push_str("\t};\n};\n");
+    out
+  }
+
+  /// Builds a `steamcmd` script with one `workshop_download_item <app_id> <id>` line per Steam mod.
+  ///
+  /// `app_id` is the Steam app the workshop items belong to, e.g. `107410` for Arma 3.
+  pub fn to_steamcmd_script(&self, app_id: impl Into<AppId>) -> String {
+    let app_id = app_id.into();
+    let mut out = String::new();
+    for m in self.steam_mods.iter() {
+      writeln!(out, "workshop_download_item {app_id} {}", m.id).unwrap();
+    };
+
+    out
+  }
+
+  /// Exports the Steam mods of this preset as a JSON manifest for Swifty and similar launchers,
+  /// an array of `{ "name": ..., "workshopId": ... }` objects. Local mods and DLCs are omitted.
+  #[cfg(feature = "serde")]
+  pub fn to_swifty_json(&self) -> String {
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct SwiftyMod<'a> {
+      name: &'a str,
+      workshop_id: WorkshopId
+    }
+
+    let mods = self.steam_mods.iter()
+      .map(|m| SwiftyMod { name: &m.display_name, workshop_id: m.id })
+      .collect::<Vec<SwiftyMod<'_>>>();
+    serde_json::to_string(&mods).expect("serializing a swifty manifest should not fail")
+  }
+}
+
+struct MarkdownText<'a>(&'a str);
+
+impl fmt::Display for MarkdownText<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for c in self.0.chars() {
+      if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|') {
+        f.write_char('\\')?;
+      };
+
+      f.write_char(c)?;
+    };
+
+    Ok(())
+  }
+}
+
+struct CsvField<'a>(&'a str);
+
+impl fmt::Display for CsvField<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.0.contains([',', '"', '\n', '\r']) {
+      write!(f, "\"{}\"", self.0.replace('"', "\"\""))
+    } else {
+      f.write_str(self.0)
+    }
+  }
+}
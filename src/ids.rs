@@ -0,0 +1,145 @@
+use crate::{STEAM_STORE_APP_URL, STEAM_WORKSHOP_URL};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+/// The id of a Steam Workshop item, see [`PresetSteamMod::id`][crate::PresetSteamMod::id].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize), serde(transparent))]
+pub struct WorkshopId(pub u64);
+
+impl WorkshopId {
+  /// The Steam Workshop URL of this item, see also [`STEAM_WORKSHOP_URL`].
+  pub fn workshop_url(self) -> String {
+    format!("{STEAM_WORKSHOP_URL}{}", self.0)
+  }
+
+  /// The Steam Workshop URL of this item under the workshop host of `hosts`.
+  pub fn workshop_url_on(self, hosts: &SteamHosts) -> String {
+    format!("https://{}/sharedfiles/filedetails/?id={}", hosts.workshop, self.0)
+  }
+}
+
+/// The id of a Steam app, see [`PresetDlc::id`][crate::PresetDlc::id].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize), serde(transparent))]
+pub struct AppId(pub u64);
+
+impl AppId {
+  /// The Steam Store URL of this app, see also [`STEAM_STORE_APP_URL`].
+  pub fn store_url(self) -> String {
+    format!("{STEAM_STORE_APP_URL}{}", self.0)
+  }
+
+  /// The Steam Store URL of this app under the store host of `hosts`.
+  pub fn store_url_on(self, hosts: &SteamHosts) -> String {
+    format!("https://{}/app/{}", hosts.store, self.0)
+  }
+}
+
+/// The id of either a Steam mod or a DLC, see [`Preset::rows`][crate::Preset::rows].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ItemId {
+  Workshop(WorkshopId),
+  App(AppId)
+}
+
+impl ItemId {
+  /// The Steam Workshop URL of a workshop item, or the Steam Store URL of an app.
+  pub fn url(self) -> String {
+    match self {
+      ItemId::Workshop(id) => id.workshop_url(),
+      ItemId::App(id) => id.store_url()
+    }
+  }
+}
+
+impl fmt::Display for ItemId {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ItemId::Workshop(id) => fmt::Display::fmt(id, f),
+      ItemId::App(id) => fmt::Display::fmt(id, f)
+    }
+  }
+}
+
+impl From<WorkshopId> for ItemId {
+  fn from(id: WorkshopId) -> Self {
+    ItemId::Workshop(id)
+  }
+}
+
+impl From<AppId> for ItemId {
+  fn from(id: AppId) -> Self {
+    ItemId::App(id)
+  }
+}
+
+/// The hosts Steam Workshop and Steam Store links are served from, for environments that
+/// reach Steam through a mirror or proxy. Defaults to the real Steam hosts.
+///
+/// Hosts are given without a protocol, `www.` or trailing `/`, and may include a path prefix,
+/// e.g. `steam-proxy.example.com/community`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SteamHosts {
+  /// Defaults to `steamcommunity.com`.
+  pub workshop: String,
+  /// Defaults to `store.steampowered.com`.
+  pub store: String
+}
+
+impl SteamHosts {
+  pub fn new(workshop: impl Into<String>, store: impl Into<String>) -> Self {
+    SteamHosts { workshop: workshop.into(), store: store.into() }
+  }
+}
+
+impl Default for SteamHosts {
+  fn default() -> Self {
+    SteamHosts::new("steamcommunity.com", "store.steampowered.com")
+  }
+}
+
+macro_rules! impl_id {
+  ($Id:ident) => {
+    impl fmt::Display for $Id {
+      fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+      }
+    }
+
+    impl FromStr for $Id {
+      type Err = ParseIntError;
+
+      fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<u64>().map($Id)
+      }
+    }
+
+    impl From<u64> for $Id {
+      fn from(id: u64) -> Self {
+        $Id(id)
+      }
+    }
+
+    impl From<$Id> for u64 {
+      fn from(id: $Id) -> Self {
+        id.0
+      }
+    }
+
+    impl PartialEq<u64> for $Id {
+      fn eq(&self, other: &u64) -> bool {
+        self.0 == *other
+      }
+    }
+  };
+}
+
+impl_id!(WorkshopId);
+impl_id!(AppId);
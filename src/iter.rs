@@ -0,0 +1,183 @@
+use crate::{AppId, Game, ItemId, ModRef, Preset, PresetDlc, PresetLocalMod, PresetSteamMod, WorkshopId};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+use std::iter::{Chain, Map};
+use std::{slice, vec};
+
+/// An owned Steam mod or local mod, see [`Preset::into_iter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum PresetMod {
+  Steam(PresetSteamMod),
+  Local(PresetLocalMod)
+}
+
+impl PresetMod {
+  pub fn as_mod_ref(&self) -> ModRef<'_> {
+    match self {
+      PresetMod::Steam(m) => ModRef::Steam(m),
+      PresetMod::Local(m) => ModRef::Local(m)
+    }
+  }
+}
+
+/// A Steam mod, local mod or DLC, for storing all items of a preset in a single flat list,
+/// see [`Preset::into_mods`] and [`Preset::from_mods`].
+///
+/// The `order` of Steam and local mods is as in [`PresetSteamMod::order`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Mod {
+  Steam {
+    id: WorkshopId,
+    name: String,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    order: Option<usize>
+  },
+  Local {
+    name: String,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    order: Option<usize>
+  },
+  Dlc { id: AppId, name: String }
+}
+
+impl Preset {
+  /// Flattens this preset into a list of all its Steam and local mods, in the same order as
+  /// [`Preset::mods_in_order`], followed by its DLCs.
+  ///
+  /// The game, name, kind and meta tags of the preset are not included, so [`Preset::from_mods`]
+  /// restores only its items. For a parsed preset, or any preset whose mods are each in order of
+  /// their [`order`][PresetSteamMod::order], restoring the items is lossless.
+  pub fn into_mods(self) -> Vec<Mod> {
+    let steam_mods = self.steam_mods.into_iter().map(|m| (m.order, Mod::Steam { id: m.id, name: m.display_name, order: m.order }));
+    let local_mods = self.local_mods.into_iter().map(|m| (m.order, Mod::Local { name: m.display_name, order: m.order }));
+    let mut mods = steam_mods.chain(local_mods).collect::<Vec<(Option<usize>, Mod)>>();
+    mods.sort_by_key(|(order, _)| order.unwrap_or(usize::MAX));
+
+    let dlcs = self.dlcs.into_iter().map(|m| Mod::Dlc { id: m.id, name: m.display_name });
+    mods.into_iter().map(|(_, m)| m).chain(dlcs).collect()
+  }
+
+  /// Creates a preset for `game` from a list of items, keeping the order of each kind of item,
+  /// as well as the [`order`][PresetSteamMod::order] of each mod.
+  pub fn from_mods(game: Game, mods: Vec<Mod>) -> Preset {
+    let mut preset = Preset::new(game);
+    for m in mods {
+      match m {
+        Mod::Steam { id, name, order } => preset.steam_mods.push(PresetSteamMod { display_name: name, id, order }),
+        Mod::Local { name, order } => preset.local_mods.push(PresetLocalMod { display_name: name, order }),
+        Mod::Dlc { id, name } => preset.dlcs.push(PresetDlc { display_name: name, id })
+      };
+    };
+
+    preset
+  }
+}
+
+/// Where an item of a preset comes from, see [`Preset::rows`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Origin {
+  Steam,
+  Local,
+  Dlc
+}
+
+impl fmt::Display for Origin {
+  /// Formats as `steam`, `local` or `dlc`, as in [`Preset::to_csv`].
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(match self {
+      Origin::Steam => "steam",
+      Origin::Local => "local",
+      Origin::Dlc => "dlc"
+    })
+  }
+}
+
+impl Preset {
+  /// Iterates over all items as `(origin, display_name, id)` rows, for tabular output.
+  ///
+  /// Steam mods come first, followed by local mods, which have no id, and then DLCs.
+  pub fn rows(&self) -> impl Iterator<Item = (Origin, &str, Option<ItemId>)> {
+    let steam_mods = self.steam_mods.iter().map(|m| (Origin::Steam, m.display_name.as_str(), Some(ItemId::Workshop(m.id))));
+    let local_mods = self.local_mods.iter().map(|m| (Origin::Local, m.display_name.as_str(), None));
+    let dlcs = self.dlcs.iter().map(|m| (Origin::Dlc, m.display_name.as_str(), Some(ItemId::App(m.id))));
+    steam_mods.chain(local_mods).chain(dlcs)
+  }
+}
+
+type SteamRefs<'p> = Map<slice::Iter<'p, PresetSteamMod>, fn(&'p PresetSteamMod) -> ModRef<'p>>;
+type LocalRefs<'p> = Map<slice::Iter<'p, PresetLocalMod>, fn(&'p PresetLocalMod) -> ModRef<'p>>;
+
+/// An iterator over all Steam mods followed by all local mods of a preset, see [`Preset::all_mods`].
+#[derive(Debug, Clone)]
+pub struct Mods<'p> {
+  inner: Chain<SteamRefs<'p>, LocalRefs<'p>>
+}
+
+impl<'p> Mods<'p> {
+  pub(crate) fn new(preset: &'p Preset) -> Self {
+    let steam_mods: SteamRefs<'p> = preset.steam_mods.iter().map(ModRef::Steam);
+    let local_mods: LocalRefs<'p> = preset.local_mods.iter().map(ModRef::Local);
+    Mods { inner: steam_mods.chain(local_mods) }
+  }
+}
+
+impl<'p> Iterator for Mods<'p> {
+  type Item = ModRef<'p>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+type SteamMods = Map<vec::IntoIter<PresetSteamMod>, fn(PresetSteamMod) -> PresetMod>;
+type LocalMods = Map<vec::IntoIter<PresetLocalMod>, fn(PresetLocalMod) -> PresetMod>;
+
+/// An owning iterator over all Steam mods followed by all local mods of a preset, see [`Preset::into_iter`].
+#[derive(Debug)]
+pub struct IntoMods {
+  inner: Chain<SteamMods, LocalMods>
+}
+
+impl Iterator for IntoMods {
+  type Item = PresetMod;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+/// Iterates over all Steam mods followed by all local mods, DLCs are not included.
+impl IntoIterator for Preset {
+  type Item = PresetMod;
+  type IntoIter = IntoMods;
+
+  fn into_iter(self) -> Self::IntoIter {
+    let steam_mods: SteamMods = self.steam_mods.into_iter().map(PresetMod::Steam);
+    let local_mods: LocalMods = self.local_mods.into_iter().map(PresetMod::Local);
+    IntoMods { inner: steam_mods.chain(local_mods) }
+  }
+}
+
+/// Iterates over all Steam mods followed by all local mods, DLCs are not included.
+impl<'p> IntoIterator for &'p Preset {
+  type Item = ModRef<'p>;
+  type IntoIter = Mods<'p>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    Mods::new(self)
+  }
+}
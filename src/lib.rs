@@ -1,22 +1,76 @@
-use scraper::{ElementRef, Html, Selector};
-#[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
-use thiserror::Error;
-
-use std::fmt;
-use std::str::FromStr;
-use std::sync::LazyLock;
-
+#[cfg(feature = "std")]
 macro_rules! lazy_selector {
-  ($selector:literal) => (LazyLock::new(|| Selector::parse($selector).unwrap()));
+  ($selector:literal) => (::std::sync::LazyLock::new(|| ::scraper::Selector::parse($selector).unwrap()));
 }
 
+#[cfg(feature = "std")]
 macro_rules! lazy_selectors {
   ($($vis:vis static $SELECTOR_NAME:ident = $selector:literal;)*) => ($(
-    $vis static $SELECTOR_NAME: LazyLock<Selector> = lazy_selector!($selector);
+    $vis static $SELECTOR_NAME: ::std::sync::LazyLock<::scraper::Selector> = lazy_selector!($selector);
   )*);
 }
 
+mod borrowed;
+mod builder;
+mod diff;
+mod export;
+mod ids;
+mod iter;
+mod names;
+mod ops;
+#[cfg(feature = "std")]
+mod parser;
+mod report;
+#[cfg(feature = "streaming")]
+mod streaming;
+mod validate;
+#[cfg(feature = "std")]
+mod workshop;
+#[cfg(feature = "std")]
+pub mod selectors;
+
+pub use crate::borrowed::{PresetRef, PresetSteamModRef, PresetLocalModRef, PresetDlcRef};
+pub use crate::builder::{MergeBuilder, MergeProvenance, PresetBuilder};
+pub use crate::diff::{DiffOptions, PresetDiff};
+pub use crate::export::OutputFormat;
+pub use crate::ids::{AppId, ItemId, SteamHosts, WorkshopId};
+pub use crate::iter::{IntoMods, Mod, Mods, Origin, PresetMod};
+pub use crate::names::{ModNameMap, NameNormalization};
+pub use crate::ops::{MergeOptions, NameResolution};
+#[cfg(feature = "std")]
+pub use crate::parser::{ParseOutcome, ParseStats, PresetParser, Warning};
+pub use crate::report::{ComparisonReport, ModRename, MultiDiff, PresetChanges};
+pub use crate::validate::ValidationIssue;
+#[cfg(feature = "reqwest")]
+pub use crate::workshop::FetchError;
+
+#[cfg(feature = "std")]
+pub use scraper::{ElementRef, Html};
+
+#[cfg(feature = "std")]
+use crate::selectors::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+use std::fmt;
+#[cfg(feature = "std")]
+use std::fs;
+use std::io;
+#[cfg(feature = "std")]
+use std::io::Read;
+#[cfg(feature = "std")]
+use std::path::Path;
+#[cfg(feature = "rayon")]
+use std::path::PathBuf;
+use std::str::FromStr;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum Game {
@@ -33,23 +87,108 @@ impl fmt::Display for Game {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl Game {
+  /// The prefix of the names of this game's preset meta tags, e.g. `arma` for `arma:Type`.
+  fn meta_namespace(self) -> &'static str {
+    match self {
+      Game::Arma => "arma",
+      Game::DayZ => "dayz"
+    }
+  }
+}
+
+impl FromStr for Game {
+  type Err = Error;
+
+  /// Parses a game from its [`Display`][fmt::Display] name or a common alias, ignoring case.
+  ///
+  /// Accepts `Arma 3`, `Arma`, `Arma3` and `A3` for [`Game::Arma`], and `DayZ` for [`Game::DayZ`].
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.trim().to_ascii_lowercase().as_str() {
+      "arma 3" | "arma" | "arma3" | "a3" => Ok(Game::Arma),
+      "dayz" => Ok(Game::DayZ),
+      _ => Err(Error::InvalidGameName(s.to_owned()))
+    }
+  }
+}
+
+/// The kind of document a preset was exported as, from the `content` of its `*:Type` meta tag.
+///
+/// Defaults to [`PresetKind::Preset`], as for JSON without a `kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum PresetKind {
+  /// A mod list, `content="list"`.
+  List,
+  /// A full preset, `content="preset"`.
+  #[default]
+  Preset
+}
+
+impl PresetKind {
+  #[cfg(feature = "std")]
+  fn from_type_value(content: &str) -> Option<Self> {
+    match content {
+      "list" => Some(PresetKind::List),
+      "preset" => Some(PresetKind::Preset),
+      _ => None
+    }
+  }
+
+  fn type_value(self) -> &'static str {
+    match self {
+      PresetKind::List => "list",
+      PresetKind::Preset => "preset"
+    }
+  }
+}
+
+impl fmt::Display for PresetKind {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(match self {
+      PresetKind::List => "List",
+      PresetKind::Preset => "Preset"
+    })
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct PresetSteamMod {
   pub display_name: String,
-  pub id: u64
+  pub id: WorkshopId,
+  /// The index of this mod's row among all mod rows of the document it was parsed from,
+  /// used to interleave Steam and local mods in their original order, see [`Preset::mods_in_order`].
+  #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+  pub order: Option<usize>
+}
+
+impl PresetSteamMod {
+  /// The Steam Workshop URL of this mod, see also [`STEAM_WORKSHOP_URL`].
+  pub fn steam_workshop_url(&self) -> String {
+    self.id.workshop_url()
+  }
+
+  /// The Steam Workshop URL of this mod under the workshop host of `hosts`.
+  pub fn steam_workshop_url_on(&self, hosts: &SteamHosts) -> String {
+    self.id.workshop_url_on(hosts)
+  }
 }
 
 impl fmt::Display for PresetSteamMod {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    write!(f, "https://{STEAM_WORKSHOP_LINK}{}: {}", self.id, self.display_name)
+    write!(f, "{STEAM_WORKSHOP_URL}{}: {}", self.id, self.display_name)
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct PresetLocalMod {
-  pub display_name: String
+  pub display_name: String,
+  /// The index of this mod's row among all mod rows of the document it was parsed from,
+  /// see [`PresetSteamMod::order`].
+  #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+  pub order: Option<usize>
 }
 
 impl fmt::Display for PresetLocalMod {
@@ -58,203 +197,1677 @@ impl fmt::Display for PresetLocalMod {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct PresetDlc {
   pub display_name: String,
-  pub id: u64
+  pub id: AppId
+}
+
+impl PresetDlc {
+  /// The Steam Store URL of this DLC, see also [`STEAM_STORE_APP_URL`].
+  pub fn steam_store_url(&self) -> String {
+    self.id.store_url()
+  }
+
+  /// The Steam Store URL of this DLC under the store host of `hosts`.
+  pub fn steam_store_url_on(&self, hosts: &SteamHosts) -> String {
+    self.id.store_url_on(hosts)
+  }
 }
 
 impl fmt::Display for PresetDlc {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    write!(f, "https://{STEAM_APP_LINK}{}: {}", self.id, self.display_name)
+    write!(f, "{STEAM_STORE_APP_URL}{}: {}", self.id, self.display_name)
   }
 }
 
+/// A borrowed reference to either a Steam mod or a local mod.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModRef<'p> {
+  Steam(&'p PresetSteamMod),
+  Local(&'p PresetLocalMod)
+}
+
+impl<'p> ModRef<'p> {
+  pub fn display_name(self) -> &'p str {
+    match self {
+      ModRef::Steam(m) => &m.display_name,
+      ModRef::Local(m) => &m.display_name
+    }
+  }
+
+  pub fn order(self) -> Option<usize> {
+    match self {
+      ModRef::Steam(m) => m.order,
+      ModRef::Local(m) => m.order
+    }
+  }
+
+  /// The workshop id of the mod, or `None` for local mods.
+  pub fn id(self) -> Option<WorkshopId> {
+    match self {
+      ModRef::Steam(m) => Some(m.id),
+      ModRef::Local(..) => None
+    }
+  }
+}
+
+impl fmt::Display for ModRef<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ModRef::Steam(m) => fmt::Display::fmt(m, f),
+      ModRef::Local(m) => fmt::Display::fmt(m, f)
+    }
+  }
+}
+
+/// A parsed preset.
+///
+/// When parsed, `steam_mods`, `local_mods` and `dlcs` are each in the order their rows appear in the
+/// document, that is, the order of the document tree as the HTML parser builds it, whichever parsing
+/// method is used. Parsing the same text always produces the same preset, so output derived from it
+/// is stable. Use [`Preset::mods_in_order`] to interleave Steam and local mods in document order.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Preset {
   pub game: Game,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub kind: PresetKind,
+  #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
   pub preset_name: Option<String>,
+  /// In document order when parsed.
+  #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
   pub steam_mods: Vec<PresetSteamMod>,
+  /// In document order when parsed.
+  #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
   pub local_mods: Vec<PresetLocalMod>,
-  pub dlcs: Vec<PresetDlc>
+  /// In document order when parsed.
+  #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+  pub dlcs: Vec<PresetDlc>,
+  /// The `name` and `content` of each `<meta>` tag in the document other than the preset type and name,
+  /// in document order. These are written back out by [`Preset::to_html`].
+  #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+  pub extra_meta: Vec<(String, String)>,
+  /// The tool that generated the preset, if the document says, found best-effort in the comments
+  /// of the document head, e.g. `Arma 3 Launcher: https://arma3.com`. This is written back out
+  /// by [`Preset::to_html`] as a comment, as is `generated_at`.
+  #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+  pub generated_by: Option<String>,
+  /// When the preset was generated, if the document says, as written in the document.
+  #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+  pub generated_at: Option<String>
 }
 
-impl fmt::Display for Preset {
+/// Counts of the items in a preset, see [`Preset::summary`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct PresetSummary {
+  pub game: Game,
+  pub preset_name: Option<String>,
+  pub steam_mod_count: usize,
+  pub local_mod_count: usize,
+  pub dlc_count: usize,
+  pub total_item_count: usize
+}
+
+impl fmt::Display for PresetSummary {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     if let Some(preset_name) = self.preset_name.as_deref() {
-      writeln!(f, "{} Preset: {preset_name}", self.game)?;
+      write!(f, "{} '{preset_name}'", self.game)?;
+    } else {
+      write!(f, "{}", self.game)?;
+    };
+
+    write!(f, ": {} steam, {} local, {} dlc", self.steam_mod_count, self.local_mod_count, self.dlc_count)
+  }
+}
+
+/// A download size estimate for the items of a preset, see [`Preset::estimate_size`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SizeEstimate {
+  /// The sum of the sizes of all items with a known size.
+  pub total_bytes: u64,
+  pub known_count: usize,
+  pub unknown_count: usize
+}
+
+impl Default for Preset {
+  /// An empty [`Game::Arma`] preset, see [`Preset::new`].
+  fn default() -> Self {
+    Preset::new(Game::Arma)
+  }
+}
+
+impl PartialOrd for Preset {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+/// Presets are ordered by [`game`][Preset::game], then by [`preset_name`][Preset::preset_name]
+/// with unnamed presets last, then by their Steam mod, local mod and DLC counts.
+/// Any remaining ties are broken by kind, then by the items themselves, then by the extra meta tags
+/// and lastly by the generation info, keeping this consistent with [`Eq`].
+impl Ord for Preset {
+  fn cmp(&self, other: &Self) -> Ordering {
+    Ord::cmp(&self.game, &other.game)
+      .then_with(|| match (&self.preset_name, &other.preset_name) {
+        (Some(a), Some(b)) => Ord::cmp(a, b),
+        (a, b) => Ord::cmp(&a.is_none(), &b.is_none())
+      })
+      .then_with(|| Ord::cmp(&self.steam_mods.len(), &other.steam_mods.len()))
+      .then_with(|| Ord::cmp(&self.local_mods.len(), &other.local_mods.len()))
+      .then_with(|| Ord::cmp(&self.dlcs.len(), &other.dlcs.len()))
+      .then_with(|| Ord::cmp(&self.kind, &other.kind))
+      .then_with(|| Ord::cmp(&self.steam_mods, &other.steam_mods))
+      .then_with(|| Ord::cmp(&self.local_mods, &other.local_mods))
+      .then_with(|| Ord::cmp(&self.dlcs, &other.dlcs))
+      .then_with(|| Ord::cmp(&self.extra_meta, &other.extra_meta))
+      .then_with(|| Ord::cmp(&self.generated_by, &other.generated_by))
+      .then_with(|| Ord::cmp(&self.generated_at, &other.generated_at))
+  }
+}
+
+impl fmt::Display for Preset {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    fmt::Display::fmt(&self.display_with_labels(&DisplayLabels::default()), f)
+  }
+}
+
+/// The labels used when displaying a preset, see [`Preset::display_with_labels`].
+///
+/// The default labels are the ones used by the [`Display`][fmt::Display] impl of [`Preset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DisplayLabels<'a> {
+  /// Replaces the game and kind in the first line, e.g. `Arma 3 Preset`, which is still followed by the preset name
+  /// when there is one. Defaults to `None`.
+  pub header: Option<&'a str>,
+  /// Defaults to `Steam`.
+  pub steam: &'a str,
+  /// Defaults to `Local`.
+  pub local: &'a str,
+  /// Defaults to `DLC`.
+  pub dlc: &'a str
+}
+
+impl Default for DisplayLabels<'_> {
+  fn default() -> Self {
+    DisplayLabels { header: None, steam: "Steam", local: "Local", dlc: "DLC" }
+  }
+}
+
+/// Displays a preset with custom labels, see [`Preset::display_with_labels`].
+#[derive(Debug, Clone, Copy)]
+pub struct PresetDisplay<'p> {
+  preset: &'p Preset,
+  labels: &'p DisplayLabels<'p>
+}
+
+impl fmt::Display for PresetDisplay<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let PresetDisplay { preset, labels } = *self;
+    match labels.header {
+      Some(header) => f.write_str(header)?,
+      None => write!(f, "{} {}", preset.game, preset.kind)?
+    };
+
+    if let Some(preset_name) = preset.preset_name.as_deref() {
+      writeln!(f, ": {preset_name}")?;
     } else {
-      writeln!(f, "{} Preset", self.game)?;
+      writeln!(f)?;
+    };
+
+    for m in preset.steam_mods.iter() {
+      writeln!(f, "{}: {m}", labels.steam)?;
+    };
+
+    for m in preset.local_mods.iter() {
+      writeln!(f, "{}: {m}", labels.local)?;
+    };
+
+    for m in preset.dlcs.iter() {
+      writeln!(f, "{}: {m}", labels.dlc)?;
     };
 
+    Ok(())
+  }
+}
+
+impl Preset {
+  /// Displays this preset like its [`Display`][fmt::Display] impl, but with the header and item labels
+  /// taken from `labels`, for presenting presets in other languages.
+  pub fn display_with_labels<'p>(&'p self, labels: &'p DisplayLabels<'p>) -> PresetDisplay<'p> {
+    PresetDisplay { preset: self, labels }
+  }
+
+  /// Creates an empty preset of kind [`PresetKind::Preset`], with no name.
+  pub fn new(game: Game) -> Self {
+    Preset {
+      game,
+      kind: PresetKind::Preset,
+      preset_name: None,
+      steam_mods: Vec::new(),
+      local_mods: Vec::new(),
+      dlcs: Vec::new(),
+      extra_meta: Vec::new(),
+      generated_by: None,
+      generated_at: None
+    }
+  }
+
+  /// Creates a preset with a Steam mod for each of `ids`, in order, and nothing else.
+  ///
+  /// Since only the ids are known, each mod's display name is its id as a placeholder,
+  /// which `Preset::resolve_names` (with the `reqwest` feature) recognizes as a name to look up.
+  pub fn from_ids<I>(game: Game, ids: I) -> Self
+  where I: IntoIterator, I::Item: Into<WorkshopId> {
+    let mut preset = Preset::new(game);
+    for id in ids {
+      let id = id.into();
+      preset.add_steam_mod(id, id.to_string());
+    };
+
+    preset
+  }
+
+  pub fn kind(&self) -> PresetKind {
+    self.kind
+  }
+
+  /// Returns true if this is a [`PresetKind::List`], a flat mod list as exported from the
+  /// [Arma 3 Launcher](https://community.bistudio.com/wiki/Arma_3:_Launcher), usually handed
+  /// to a server to load its `-mod=` list from.
+  pub fn is_server_list(&self) -> bool {
+    self.kind == PresetKind::List
+  }
+
+  /// Returns true if this is a [`PresetKind::Preset`], a launcher preset meant to be
+  /// imported and loaded by players on the client, see [`Preset::is_server_list`].
+  pub fn is_client_preset(&self) -> bool {
+    self.kind == PresetKind::Preset
+  }
+
+  /// Iterates over all Steam mods followed by all local mods, the same as iterating over `&Preset`.
+  ///
+  /// DLCs are not included, since they are not mods.
+  pub fn all_mods(&self) -> Mods<'_> {
+    Mods::new(self)
+  }
+
+  /// Iterates over all Steam and local mods, interleaved in the order they appeared in the
+  /// document they were parsed from.
+  ///
+  /// Mods without an [`order`][PresetSteamMod::order] come after those with one,
+  /// Steam mods first, otherwise in the same order as [`Preset::all_mods`].
+  pub fn mods_in_order(&self) -> impl Iterator<Item = ModRef<'_>> {
+    let mut mods = self.all_mods().collect::<Vec<ModRef<'_>>>();
+    mods.sort_by_key(|m| m.order().unwrap_or(usize::MAX));
+    mods.into_iter()
+  }
+
+  /// Iterates over the DLCs of this preset, which [`Preset::all_mods`] leaves out.
+  ///
+  /// ```
+  /// use a3_preset_parser::{Game, PresetBuilder};
+  ///
+  /// let preset = PresetBuilder::new(Game::Arma)
+  ///   .steam_mod(450814997, "CBA_A3")
+  ///   .dlc(1021790, "Contact")
+  ///   .build();
+  /// let dlc_names = preset.dlcs_iter().map(|dlc| dlc.display_name.as_str()).collect::<Vec<_>>();
+  /// assert_eq!(dlc_names, ["Contact"]);
+  /// ```
+  pub fn dlcs_iter(&self) -> std::slice::Iter<'_, PresetDlc> {
+    self.dlcs.iter()
+  }
+
+  /// Consumes this preset, returning its DLCs.
+  ///
+  /// ```
+  /// use a3_preset_parser::{Game, PresetBuilder};
+  ///
+  /// let preset = PresetBuilder::new(Game::Arma).dlc(1021790, "Contact").build();
+  /// let dlcs = preset.into_dlcs();
+  /// assert_eq!(dlcs[0].id, 1021790);
+  /// ```
+  pub fn into_dlcs(self) -> Vec<PresetDlc> {
+    self.dlcs
+  }
+
+  /// The number of Steam mods plus local mods, not including DLCs.
+  pub fn mod_count(&self) -> usize {
+    self.steam_mods.len() + self.local_mods.len()
+  }
+
+  pub fn steam_mod_count(&self) -> usize {
+    self.steam_mods.len()
+  }
+
+  pub fn local_mod_count(&self) -> usize {
+    self.local_mods.len()
+  }
+
+  pub fn dlc_count(&self) -> usize {
+    self.dlcs.len()
+  }
+
+  /// The number of Steam mods, local mods and DLCs.
+  pub fn total_item_count(&self) -> usize {
+    self.mod_count() + self.dlc_count()
+  }
+
+  pub fn summary(&self) -> PresetSummary {
+    PresetSummary {
+      game: self.game,
+      preset_name: self.preset_name.clone(),
+      steam_mod_count: self.steam_mod_count(),
+      local_mod_count: self.local_mod_count(),
+      dlc_count: self.dlc_count(),
+      total_item_count: self.total_item_count()
+    }
+  }
+
+  /// Estimates the download size of this preset, by summing the size of each Steam mod and DLC
+  /// as looked up by [`ItemId`] with `size_of`, in bytes.
+  ///
+  /// Items for which `size_of` returns `None` are left out of the total, and counted in
+  /// [`SizeEstimate::unknown_count`]. Local mods are not included, since they are not downloaded.
+  pub fn estimate_size<F: FnMut(ItemId) -> Option<u64>>(&self, mut size_of: F) -> SizeEstimate {
+    let ids = self.steam_mods.iter().map(|m| ItemId::Workshop(m.id)).chain(self.dlcs.iter().map(|m| ItemId::App(m.id)));
+    ids.fold(SizeEstimate::default(), |mut estimate, id| {
+      match size_of(id) {
+        Some(size) => {
+          estimate.total_bytes = estimate.total_bytes.saturating_add(size);
+          estimate.known_count += 1;
+        },
+        None => estimate.unknown_count += 1
+      };
+
+      estimate
+    })
+  }
+
+  /// Returns true if this preset has no mods and no DLCs.
+  pub fn is_empty(&self) -> bool {
+    self.total_item_count() == 0
+  }
+
+  /// Returns true if any Steam or local mod has this display name, compared as in [`Preset::dedupe_local_mods`].
+  pub fn contains_mod_named(&self, name: &str) -> bool {
+    self.contains_mod_named_with(name, NameNormalization::default())
+  }
+
+  /// Like [`Preset::contains_mod_named`], but with names compared as normalized by `normalization`.
+  pub fn contains_mod_named_with(&self, name: &str, normalization: NameNormalization) -> bool {
+    let name = normalization.normalize(name);
+    self.all_mods().any(|m| normalization.normalize(m.display_name()) == name)
+  }
+
+  pub fn steam_mod_ids(&self) -> Vec<WorkshopId> {
+    self.steam_mods.iter().map(|m| m.id).collect()
+  }
+
+  pub fn dlc_ids(&self) -> Vec<AppId> {
+    self.dlcs.iter().map(|m| m.id).collect()
+  }
+
+  pub fn contains_steam_id(&self, id: impl Into<WorkshopId>) -> bool {
+    let id = id.into();
+    self.steam_mods.iter().any(|m| m.id == id)
+  }
+
+  pub fn contains_dlc_id(&self, id: impl Into<AppId>) -> bool {
+    let id = id.into();
+    self.dlcs.iter().any(|m| m.id == id)
+  }
+
+  /// Groups the Steam mods by the key `f` returns for each of them, such as a category or
+  /// the first letter of the name. Within each group, mods keep their order in this preset.
+  pub fn group_steam_mods_by<K: Ord, F: FnMut(&PresetSteamMod) -> K>(&self, mut f: F) -> BTreeMap<K, Vec<&PresetSteamMod>> {
+    let mut groups = BTreeMap::<K, Vec<&PresetSteamMod>>::new();
     for m in self.steam_mods.iter() {
-      writeln!(f, "Steam: {m}")?;
+      groups.entry(f(m)).or_default().push(m);
     };
 
-    for m in self.local_mods.iter() {
-      writeln!(f, "Local: {m}")?;
+    groups
+  }
+
+  /// Writes the same text as the [`Display`][fmt::Display] impl directly to `w`, without
+  /// building an intermediate string.
+  #[cfg(feature = "std")]
+  pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+    write!(w, "{self}")
+  }
+
+  /// Serializes this preset as JSON, following the shape of its fields.
+  #[cfg(feature = "serde")]
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).expect("serializing a preset should not fail")
+  }
+
+  /// Deserializes a preset from JSON produced by [`Preset::to_json`].
+  #[cfg(feature = "serde")]
+  pub fn from_json(json: &str) -> Result<Preset, serde_json::Error> {
+    serde_json::from_str(json)
+  }
+
+  /// Serializes this preset into the HTML format used by the launcher,
+  /// such that it can be imported back into the launcher or re-parsed.
+  pub fn to_html(&self) -> String {
+    let mut out = String::new();
+    self.write_html(&mut out).expect("writing to a string should not fail");
+    out
+  }
+
+  /// Like [`Preset::to_html`], but writes into an existing [`fmt::Write`].
+  pub fn write_html<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+    let namespace = self.game.meta_namespace();
+
+    writeln!(w, "<?xml version=\"1.0\" encoding=\"utf-8\"?>")?;
+    writeln!(w, "<html>")?;
+    if let Some(generated_by) = self.generated_by.as_deref().filter(|text| is_comment_safe(text)) {
+      writeln!(w, "  <!--Created by {generated_by}-->")?;
     };
+    if let Some(generated_at) = self.generated_at.as_deref().filter(|text| is_comment_safe(text)) {
+      writeln!(w, "  <!--Created at {generated_at}-->")?;
+    };
+    writeln!(w, "  <head>")?;
+    writeln!(w, "    <meta name=\"{namespace}:Type\" content=\"{}\" />", self.kind.type_value())?;
+    if let Some(preset_name) = self.preset_name.as_deref() {
+      writeln!(w, "    <meta name=\"{namespace}:PresetName\" content=\"{}\" />", escape_attr(preset_name))?;
+    };
+    for (name, content) in self.extra_meta.iter() {
+      writeln!(w, "    <meta name=\"{}\" content=\"{}\" />", escape_attr(name), escape_attr(content))?;
+    };
+    writeln!(w, "    <title>{}</title>", self.game)?;
+    writeln!(w, "  </head>")?;
+    writeln!(w, "  <body>")?;
+    writeln!(w, "    <div class=\"mod-list\">")?;
+    writeln!(w, "      <table>")?;
+
+    for m in self.mods_in_order() {
+      writeln!(w, "        <tr data-type=\"ModContainer\">")?;
+      writeln!(w, "          <td data-type=\"DisplayName\">{}</td>", escape_text(m.display_name()))?;
+      match m {
+        ModRef::Steam(m) => {
+          let link = format_args!("{STEAM_WORKSHOP_URL}{}", m.id);
+          writeln!(w, "          <td>")?;
+          writeln!(w, "            <span class=\"from-steam\">Steam</span>")?;
+          writeln!(w, "          </td>")?;
+          writeln!(w, "          <td>")?;
+          writeln!(w, "            <a href=\"{link}\" data-type=\"Link\">{link}</a>")?;
+          writeln!(w, "          </td>")?;
+        },
+        ModRef::Local(..) => {
+          writeln!(w, "          <td>")?;
+          writeln!(w, "            <span class=\"from-local\">Local</span>")?;
+          writeln!(w, "          </td>")?;
+          writeln!(w, "          <td></td>")?;
+        }
+      };
+      writeln!(w, "        </tr>")?;
+    };
+
+    writeln!(w, "      </table>")?;
+    writeln!(w, "    </div>")?;
+    writeln!(w, "    <div class=\"dlc-list\">")?;
+    writeln!(w, "      <table>")?;
 
     for m in self.dlcs.iter() {
-      writeln!(f, "DLC: {m}")?;
+      let link = format_args!("{STEAM_STORE_APP_URL}{}", m.id);
+      writeln!(w, "        <tr data-type=\"DlcContainer\">")?;
+      writeln!(w, "          <td data-type=\"DisplayName\">{}</td>", escape_text(&m.display_name))?;
+      writeln!(w, "          <td>")?;
+      writeln!(w, "            <a href=\"{link}\" data-type=\"Link\">{link}</a>")?;
+      writeln!(w, "          </td>")?;
+      writeln!(w, "        </tr>")?;
     };
 
+    writeln!(w, "      </table>")?;
+    writeln!(w, "    </div>")?;
+    writeln!(w, "  </body>")?;
+    writeln!(w, "</html>")?;
+
     Ok(())
   }
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
+  #[error("document is not a preset, no preset type or name meta tags were found")]
+  NotAPreset,
+  #[error("document nests elements more than {MAX_NESTING_DEPTH} deep")]
+  NestingTooDeep,
   #[error("preset type selector failed on html: {0}")]
   SelectorFailedPresetType(String),
   #[error("invalid preset type value {0:?}, expected one of 'preset' or 'list'")]
   InvalidPresetTypeValue(String),
-  #[error("item origin selector failed on html: {0}")]
-  SelectorFailedItemOrigin(String),
-  #[error("invalid item origin value {0:?}, expected one of 'from-local' or 'from-steam'")]
-  InvalidItemOriginValue(String),
-  #[error("item name selector failed on html: {0}")]
-  SelectorFailedItemName(String),
-  #[error("item link selector failed on html: {0}")]
-  SelectorFailedItemLink(String),
-  #[error("invalid item link value {0:?}, failed to extract steam workshop item id")]
-  InvalidItemLinkSteamWorkshop(String),
-  #[error("invalid item link value {0:?}, failed to extract steam app item id")]
-  InvalidItemLinkSteamApp(String)
+  #[error("item origin selector failed{} on html: {0}", at(.1))]
+  SelectorFailedItemOrigin(String, Option<SourceLocation>),
+  #[error("invalid item origin value {0:?}{}, expected one of 'from-local' or 'from-steam'", at(.1))]
+  InvalidItemOriginValue(String, Option<SourceLocation>),
+  #[error("item name selector failed{} on html: {0}", at(.1))]
+  SelectorFailedItemName(String, Option<SourceLocation>),
+  #[error("item link selector failed{} on html: {0}", at(.1))]
+  SelectorFailedItemLink(String, Option<SourceLocation>),
+  #[error("invalid item link value {0:?}{}, failed to extract steam workshop item id", at(.1))]
+  InvalidItemLinkSteamWorkshop(String, Option<SourceLocation>),
+  #[error("invalid item link value {0:?}{}, failed to extract steam app item id", at(.1))]
+  InvalidItemLinkSteamApp(String, Option<SourceLocation>),
+  #[error("failed to parse mod row {index}: {source}")]
+  InvalidModRow { index: usize, source: Box<Error> },
+  #[error("failed to parse dlc row {index}: {source}")]
+  InvalidDlcRow { index: usize, source: Box<Error> },
+  #[error("steam workshop id {0} appears more than once")]
+  DuplicateSteamId(WorkshopId),
+  #[error("dlc id {0} appears more than once")]
+  DuplicateDlcId(AppId),
+  #[error("presets are for different games, {a} and {b}")]
+  GameMismatch { a: Game, b: Game },
+  #[error("failed to read or write preset: {0}")]
+  Io(#[from] io::Error),
+  #[error("invalid game name {0:?}, expected one of 'Arma 3' or 'DayZ'")]
+  InvalidGameName(String),
+  #[error("invalid output format {0:?}, expected one of 'text', 'html', 'json', 'csv' or 'markdown'")]
+  InvalidOutputFormat(String)
 }
 
-impl FromStr for Preset {
-  type Err = Error;
+/// A broad category of [`Error`], see [`Error::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+  /// The document is not a preset, or its preset type could not be found.
+  NotAPreset,
+  /// The document nests elements too deeply to be parsed, see [`MAX_NESTING_DEPTH`].
+  NestingTooDeep,
+  /// A mod or DLC row is missing its name, origin or link.
+  MalformedItem,
+  /// A mod or DLC row's link does not contain a Steam id.
+  BadLink,
+  /// A value is not one of those expected, such as a preset type, item origin, game name or output format.
+  UnknownValue,
+  /// A Steam workshop id or DLC id appears more than once.
+  DuplicateId,
+  /// Two presets are for different games.
+  GameMismatch,
+  /// Reading or writing failed.
+  Io
+}
 
-  fn from_str(document_text: &str) -> Result<Self, Self::Err> {
-    lazy_selectors!{
-      static SELECTOR_PRESET_TYPE_ARMA = "head > meta[name=\"arma:Type\"][content]";
-      static SELECTOR_PRESET_NAME_ARMA = "head > meta[name=\"arma:PresetName\"][content]";
-      static SELECTOR_PRESET_TYPE_DAYZ = "head > meta[name=\"dayz:Type\"][content]";
-      static SELECTOR_PRESET_NAME_DAYZ = "head > meta[name=\"dayz:PresetName\"][content]";
-      static SELECTOR_MOD_CONTAINER = "body > div.mod-list > table tr[data-type=\"ModContainer\"]";
-      static SELECTOR_DLC_CONTAINER = "body > div.dlc-list > table tr[data-type=\"DlcContainer\"]";
-      static SELECTOR_ITEM_NAME = "td[data-type=\"DisplayName\"]";
-      static SELECTOR_ITEM_LINK = "td > a[data-type=\"Link\"]";
-      static SELECTOR_ITEM_ORIGIN = "td > span[class]";
+impl Error {
+  /// The category of this error. Row errors from lenient parsing take the category of the error they wrap.
+  pub fn kind(&self) -> ErrorKind {
+    match self {
+      Error::NotAPreset | Error::SelectorFailedPresetType(..) => ErrorKind::NotAPreset,
+      Error::NestingTooDeep => ErrorKind::NestingTooDeep,
+      Error::SelectorFailedItemOrigin(..) | Error::SelectorFailedItemName(..) |
+      Error::SelectorFailedItemLink(..) => ErrorKind::MalformedItem,
+      Error::InvalidItemLinkSteamWorkshop(..) | Error::InvalidItemLinkSteamApp(..) => ErrorKind::BadLink,
+      Error::InvalidPresetTypeValue(..) | Error::InvalidItemOriginValue(..) |
+      Error::InvalidGameName(..) | Error::InvalidOutputFormat(..) => ErrorKind::UnknownValue,
+      Error::InvalidModRow { source, .. } | Error::InvalidDlcRow { source, .. } => source.kind(),
+      Error::DuplicateSteamId(..) | Error::DuplicateDlcId(..) => ErrorKind::DuplicateId,
+      Error::GameMismatch { .. } => ErrorKind::GameMismatch,
+      Error::Io(..) => ErrorKind::Io
     }
+  }
 
-    fn select_preset_type(document: &Html) -> Result<Game, Error> {
-      let [arma, dayz] = [
-        (&SELECTOR_PRESET_TYPE_ARMA, Game::Arma),
-        (&SELECTOR_PRESET_TYPE_DAYZ, Game::DayZ)
-      ].map(|(selector, game)| {
-        document.select(selector).next()
-          .and_then(|element| element.value().attr("content"))
-          .ok_or_else(|| Error::SelectorFailedPresetType(document.html()))
-          .and_then(|content| if ["list", "preset"].contains(&content) {
-            Ok(game)
-          } else {
-            Err(Error::InvalidPresetTypeValue(content.to_owned()))
-          })
-      });
+  /// Returns true if this error only concerns a single mod or DLC row, such that the rest of the
+  /// preset can still be parsed by skipping that row, as [`Preset::parse_lenient`] does.
+  pub fn is_recoverable(&self) -> bool {
+    matches!(self,
+      Error::SelectorFailedItemOrigin(..) | Error::InvalidItemOriginValue(..) |
+      Error::SelectorFailedItemName(..) | Error::SelectorFailedItemLink(..) |
+      Error::InvalidItemLinkSteamWorkshop(..) | Error::InvalidItemLinkSteamApp(..) |
+      Error::InvalidModRow { .. } | Error::InvalidDlcRow { .. }
+    )
+  }
 
-      Result::or(arma, dayz)
+  /// The location of the row this error was caused by in the original document text, if known.
+  pub fn location(&self) -> Option<SourceLocation> {
+    match self {
+      Error::SelectorFailedItemOrigin(_, location) |
+      Error::InvalidItemOriginValue(_, location) |
+      Error::SelectorFailedItemName(_, location) |
+      Error::SelectorFailedItemLink(_, location) |
+      Error::InvalidItemLinkSteamWorkshop(_, location) |
+      Error::InvalidItemLinkSteamApp(_, location) => *location,
+      Error::InvalidModRow { source, .. } | Error::InvalidDlcRow { source, .. } => source.location(),
+      _ => None
     }
+  }
 
-    fn select_preset_name_arma(document: &Html) -> Option<&str> {
-      document.select(&SELECTOR_PRESET_NAME_ARMA).next()
-        .and_then(|element| element.value().attr("content"))
-    }
+  #[cfg(feature = "std")]
+  fn with_location(mut self, new_location: Option<SourceLocation>) -> Self {
+    match &mut self {
+      Error::SelectorFailedItemOrigin(_, location) |
+      Error::InvalidItemOriginValue(_, location) |
+      Error::SelectorFailedItemName(_, location) |
+      Error::SelectorFailedItemLink(_, location) |
+      Error::InvalidItemLinkSteamWorkshop(_, location) |
+      Error::InvalidItemLinkSteamApp(_, location) => *location = new_location,
+      _ => ()
+    };
 
-    fn select_preset_name_dayz(document: &Html) -> Option<&str> {
-      document.select(&SELECTOR_PRESET_NAME_DAYZ).next()
-        .and_then(|element| element.value().attr("content"))
+    self
+  }
+}
+
+/// A position within a document's text, pointing at the start of a tag.
+///
+/// Since the HTML parser does not track positions, these are found by searching the original
+/// text for the row's `data-type` marker, and so are approximate for unusually formatted documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLocation {
+  /// The byte offset from the start of the text.
+  pub offset: usize,
+  /// The line number, starting from 1.
+  pub line: usize,
+  /// The column number in characters, starting from 1.
+  pub column: usize
+}
+
+/// Finds the tags containing successive occurrences of a marker in a document's text.
+///
+/// Each search picks up where the last one left off, including the line and column counts,
+/// so that locating every row of a document with many failing rows stays linear in its length.
+#[cfg(feature = "std")]
+struct SourceLocator<'t> {
+  text: &'t str,
+  markers: std::str::MatchIndices<'t, &'t str>,
+  next_index: usize,
+  last: SourceLocation
+}
+
+#[cfg(feature = "std")]
+impl<'t> SourceLocator<'t> {
+  fn new(text: &'t str, marker: &'t str) -> Self {
+    SourceLocator {
+      text,
+      markers: text.match_indices(marker),
+      next_index: 0,
+      last: SourceLocation { offset: 0, line: 1, column: 1 }
     }
+  }
+
+  /// Finds the tag containing the `index`th occurrence of the marker,
+  /// `index` must be greater than that of any previous call.
+  fn locate(&mut self, index: usize) -> Option<SourceLocation> {
+    let (marker_offset, _) = self.markers.nth(index.checked_sub(self.next_index)?)?;
+    self.next_index = index + 1;
+
+    let last_offset = self.last.offset;
+    let offset = self.text[last_offset..marker_offset].rfind('<').map_or(marker_offset, |i| last_offset + i);
+    let skipped = &self.text[last_offset..offset];
+    self.last = match skipped.rfind('\n') {
+      Some(i) => SourceLocation {
+        offset,
+        line: self.last.line + skipped.matches('\n').count(),
+        column: skipped[i + 1..].chars().count() + 1
+      },
+      None => SourceLocation {
+        offset,
+        line: self.last.line,
+        column: self.last.column + skipped.chars().count()
+      }
+    };
+
+    Some(self.last)
+  }
+}
 
-    fn select_item_name(element: ElementRef<'_>) -> Result<&str, Error> {
-      element.select(&SELECTOR_ITEM_NAME).next()
-        .and_then(|element| element.text().next())
-        .ok_or_else(|| Error::SelectorFailedItemName(element.inner_html()))
+impl fmt::Display for SourceLocation {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "line {}, column {}", self.line, self.column)
+  }
+}
+
+fn at(location: &Option<SourceLocation>) -> impl fmt::Display + '_ {
+  struct At<'a>(&'a Option<SourceLocation>);
+
+  impl fmt::Display for At<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      match self.0 {
+        Some(location) => write!(f, " at {location}"),
+        None => Ok(())
+      }
     }
+  }
+
+  At(location)
+}
+
+impl Preset {
+  /// Reads a preset from `reader` until EOF, then parses it.
+  ///
+  /// The input must be UTF-8, otherwise this fails with [`Error::Io`].
+  /// A leading UTF-8 byte order mark is skipped if present, as with [`FromStr`].
+  #[cfg(feature = "std")]
+  pub fn from_reader<R: Read>(mut reader: R) -> Result<Preset, Error> {
+    let mut document_text = String::new();
+    reader.read_to_string(&mut document_text)?;
+    document_text.parse()
+  }
+
+  /// Reads a gzip-compressed preset from `reader` until EOF, then decompresses and parses it.
+  #[cfg(feature = "flate2")]
+  pub fn from_gz_reader<R: Read>(reader: R) -> Result<Preset, Error> {
+    Preset::from_reader(flate2::read::GzDecoder::new(reader))
+  }
+
+  /// Reads and parses the preset file at `path`, see [`Preset::from_reader`].
+  ///
+  /// With the `flate2` feature, a file starting with the gzip magic bytes is decompressed
+  /// before parsing, see [`Preset::from_gz_reader`].
+  #[cfg(feature = "std")]
+  pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Preset, Error> {
+    let document_bytes = fs::read(path)?;
+    #[cfg(feature = "flate2")]
+    if document_bytes.starts_with(&[0x1f, 0x8b]) {
+      return Preset::from_gz_reader(document_bytes.as_slice());
+    };
+
+    Preset::from_reader(document_bytes.as_slice())
+  }
+
+  /// Writes the same text as the [`Display`][fmt::Display] impl to the file at `path`,
+  /// creating or truncating it.
+  ///
+  /// This is a plain text summary, which [`Preset::from_file`] cannot read back,
+  /// use [`Preset::to_html_file`] to write a preset file.
+  #[cfg(feature = "std")]
+  pub fn to_summary_file<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+    fs::write(path, self.to_string())?;
+    Ok(())
+  }
+
+  /// Turns the preset name into a file name stem that is valid on both Windows and Unix.
+  ///
+  /// Characters that are not allowed in file names (`< > : " / \ | ? *` and control characters)
+  /// are replaced with `_`, whitespace is collapsed, and trailing dots and spaces are removed.
+  /// Names reserved on Windows, like `CON` or `LPT1`, get a `_` appended before any extension. Presets without a usable
+  /// name fall back to the game and kind, e.g. `Arma 3 Preset`.
+  pub fn safe_file_stem(&self) -> String {
+    const RESERVED: &[&str] = &[
+      "CON", "PRN", "AUX", "NUL",
+      "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+      "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9"
+    ];
+
+    let name = collapse_whitespace(Cow::Borrowed(self.preset_name.as_deref().unwrap_or("")));
+    let name = name.replace(|c: char| c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*'), "_");
+    let mut stem = name.trim_end_matches(['.', ' ']).to_owned();
+    if stem.is_empty() {
+      stem = format!("{} {}", self.game, self.kind);
+    };
+
+    let base_name_len = stem.find('.').unwrap_or(stem.len());
+    if RESERVED.iter().any(|reserved| stem[..base_name_len].eq_ignore_ascii_case(reserved)) {
+      stem.insert(base_name_len, '_');
+    };
+
+    stem
+  }
+
+  /// Writes this preset as HTML to the file at `path`, creating or truncating it, see [`Preset::to_html`].
+  #[cfg(feature = "std")]
+  pub fn to_html_file<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+    fs::write(path, self.to_html())?;
+    Ok(())
+  }
 
-    fn select_item_link(element: ElementRef<'_>) -> Result<&str, Error> {
-      element.select(&SELECTOR_ITEM_LINK).next()
-        .and_then(|element| element.value().attr("href"))
-        .ok_or_else(|| Error::SelectorFailedItemLink(element.inner_html()))
+  /// Parses a preset, skipping over any mod or DLC rows that fail to parse instead of bailing.
+  ///
+  /// Skipped rows are reported as [`Error::InvalidModRow`] or [`Error::InvalidDlcRow`].
+  /// Returns `None` only if the preset type could not be determined, with that error last.
+  #[cfg(feature = "std")]
+  pub fn parse_lenient(document_text: &str) -> (Option<Preset>, Vec<Error>) {
+    let mut errors = Vec::new();
+    match parse_preset(document_text, ParseOptions::default(), Some(&mut errors), None) {
+      Ok(preset) => (Some(preset), errors),
+      Err(err) => {
+        errors.push(err);
+        (None, errors)
+      }
     }
+  }
+
+  /// Parses a preset like [`FromStr`], also returning [`ParseStats`] for diagnostics,
+  /// see [`PresetParser::parse_with_stats`].
+  #[cfg(feature = "std")]
+  pub fn parse_with_stats(document_text: &str) -> Result<(Preset, ParseStats), Error> {
+    PresetParser::default().parse_with_stats(document_text)
+  }
+
+  /// Parses a preset like [`FromStr`], but accepts any value for the `arma:Type` or `dayz:Type`
+  /// meta tag, or no such tag at all, inferring the game from which namespace of `*:Type` or
+  /// `*:PresetName` meta tags is present.
+  ///
+  /// If the type is missing, or not one of `preset` or `list`, the kind defaults to [`PresetKind::Preset`].
+  #[cfg(feature = "std")]
+  pub fn parse_best_effort(document_text: &str) -> Result<Preset, Error> {
+    parse_preset(document_text, ParseOptions { best_effort: true, ..ParseOptions::default() }, None, None)
+  }
+
+  /// Parses a preset like [`FromStr`], but fails with [`Error::DuplicateSteamId`] or
+  /// [`Error::DuplicateDlcId`] if the same Steam workshop id or DLC id appears more than once.
+  #[cfg(feature = "std")]
+  pub fn parse_strict(document_text: &str) -> Result<Preset, Error> {
+    parse_preset(document_text, ParseOptions { strict_ids: true, ..ParseOptions::default() }, None, None)
+  }
+
+  /// Parses a preset from an already parsed document, borrowing names from it instead of
+  /// allocating a new string for each one.
+  ///
+  /// Since [`Html`] stores its own copy of the document's text, the result borrows from
+  /// `document` rather than the original text, so `document` must outlive the [`PresetRef`].
+  /// Use [`PresetRef::to_owned`] to get a [`Preset`] that can outlive it.
+  ///
+  /// Unlike [`FromStr`], this will not strip a leading byte order mark, since the text has already been parsed.
+  #[cfg(feature = "std")]
+  pub fn parse_borrowed(document: &Html) -> Result<PresetRef<'_>, Error> {
+    parse_document(document, None, ParseOptions::default(), None, None)
+  }
+
+  /// Parses a preset from an already parsed document, without parsing it again, see also [`Preset::parse_borrowed`].
+  ///
+  /// Since the original text is not available, errors will not have a [`SourceLocation`].
+  #[cfg(feature = "std")]
+  pub fn from_document(document: &Html) -> Result<Preset, Error> {
+    Preset::parse_borrowed(document).map(Preset::from)
+  }
+
+  /// Parses the mod and DLC rows within `root`, for presets embedded in a larger page,
+  /// or documents that were fixed up before parsing.
+  ///
+  /// Rows are found as with the [`selectors`], except that the `div.mod-list` and `div.dlc-list`
+  /// may be anywhere within `root`, or be `root` itself. Meta tags are not read, so the game is
+  /// supplied by `game`, the kind is always [`PresetKind::Preset`], and the preset has no name.
+  /// Errors will not have a [`SourceLocation`].
+  #[cfg(feature = "std")]
+  pub fn from_element(root: ElementRef<'_>, game: Game) -> Result<Preset, Error> {
+    let mod_rows = root.select(&SELECTOR_SUBTREE_MOD_CONTAINER);
+    let dlc_rows = root.select(&SELECTOR_SUBTREE_DLC_CONTAINER);
+    let rows = parse_rows(mod_rows, dlc_rows, None, ParseOptions::default(), None, None)?;
+    Ok(Preset {
+      steam_mods: rows.steam_mods.into_iter().map(PresetSteamMod::from).collect(),
+      local_mods: rows.local_mods.into_iter().map(PresetLocalMod::from).collect(),
+      dlcs: rows.dlcs.into_iter().map(PresetDlc::from).collect(),
+      ..Preset::new(game)
+    })
+  }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<&Html> for Preset {
+  type Error = Error;
+
+  /// See [`Preset::from_document`].
+  fn try_from(document: &Html) -> Result<Self, Self::Error> {
+    Preset::from_document(document)
+  }
+}
+
+/// Reads and parses each of the preset files at `paths` in parallel.
+///
+/// The results are returned in the same order as `paths`, failing to read a file results in [`Error::Io`].
+/// This relies on [`Error`] being [`Send`], which it is, as it only holds owned data.
+#[cfg(feature = "rayon")]
+pub fn parse_many<P: AsRef<Path> + Sync>(paths: &[P]) -> Vec<(PathBuf, Result<Preset, Error>)> {
+  use rayon::prelude::*;
+
+  paths.par_iter()
+    .map(|path| {
+      let path = path.as_ref();
+      let result = Preset::from_file(path);
+      (path.to_owned(), result)
+    })
+    .collect()
+}
+
+#[cfg(feature = "std")]
+impl FromStr for Preset {
+  type Err = Error;
+
+  fn from_str(document_text: &str) -> Result<Self, Self::Err> {
+    parse_preset(document_text, ParseOptions::default(), None, None)
+  }
+}
+
+#[cfg(feature = "std")]
+lazy_selectors!{
+  static SELECTOR_HEAD = "head";
+  static SELECTOR_META = "head > meta[name][content]";
+  static SELECTOR_SUBTREE_MOD_CONTAINER = "div.mod-list > table tr[data-type=\"ModContainer\"]";
+  static SELECTOR_SUBTREE_DLC_CONTAINER = "div.dlc-list > table tr[data-type=\"DlcContainer\"]";
+}
+
+#[cfg(feature = "std")]
+enum ParsedMod<'a> {
+  Steam(PresetSteamModRef<'a>),
+  Local(PresetLocalModRef<'a>)
+}
 
-    fn select_item_origin(element: ElementRef<'_>) -> Result<&str, Error> {
-      element.select(&SELECTOR_ITEM_ORIGIN).next()
-        .and_then(|element| element.value().attr("class"))
-        .ok_or_else(|| Error::SelectorFailedItemOrigin(element.inner_html()))
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+struct ParseOptions<'h> {
+  /// Accept unrecognized or missing `*:Type` meta values, defaulting to [`PresetKind::Preset`].
+  best_effort: bool,
+  /// Reject Steam mods or DLCs whose id appears more than once.
+  strict_ids: bool,
+  /// Trim item names and collapse whitespace within them.
+  normalize_names: bool,
+  /// Infer the origin of mod rows without an origin span from their link.
+  infer_origin: bool,
+  /// Parse documents without any preset meta tags, but with mod or DLC rows, as Arma 3 presets.
+  fragments: bool,
+  /// Alternate hosts to accept Steam links under, in addition to the real Steam hosts.
+  hosts: Option<&'h SteamHosts>
+}
+
+#[cfg(feature = "std")]
+impl Default for ParseOptions<'_> {
+  fn default() -> Self {
+    ParseOptions {
+      best_effort: false,
+      strict_ids: false,
+      normalize_names: true,
+      infer_origin: true,
+      fragments: false,
+      hosts: None
     }
+  }
+}
 
-    let document = Html::parse_document(&document_text);
+/// When `errors` is provided, row errors are collected into it instead of being returned.
+/// When `warnings` is provided, recoveries made while parsing are reported into it.
+#[cfg(feature = "std")]
+fn parse_preset(
+  document_text: &str,
+  options: ParseOptions<'_>,
+  errors: Option<&mut Vec<Error>>,
+  warnings: Option<&mut Vec<Warning>>
+) -> Result<Preset, Error> {
+  let document = parse_html(document_text.strip_prefix('\u{feff}').unwrap_or(document_text))?;
+  parse_document(&document, Some(document_text), options, errors, warnings).map(Preset::from)
+}
 
-    let game = select_preset_type(&document)?;
+/// The deepest that a document's elements may be nested for it to be parsed.
+///
+/// Building the tree of a document takes time quadratic in how deeply its elements are nested,
+/// so deeper documents are rejected with [`Error::NestingTooDeep`] before being parsed.
+/// Presets exported by the launcher nest their elements less than ten deep.
+pub const MAX_NESTING_DEPTH: usize = 512;
+
+#[cfg(feature = "std")]
+fn parse_html(document_text: &str) -> Result<Html, Error> {
+  if nesting_depth_exceeds(document_text, MAX_NESTING_DEPTH) {
+    return Err(Error::NestingTooDeep);
+  };
+
+  Ok(Html::parse_document(document_text))
+}
 
-    let preset_name = match game {
-      Game::Arma => select_preset_name_arma(&document),
-      Game::DayZ => select_preset_name_dayz(&document),
+/// Returns true if a document's elements are nested more than `limit` deep.
+///
+/// This follows the document's tags rather than building its tree: start tags of non-void
+/// elements open an element, end tags close the innermost open element with the same name
+/// and any within it, and stray end tags are ignored, as are comments, doctypes and the
+/// contents of raw text elements. Elements closed implicitly, such as a `<p>` followed by
+/// another `<p>`, are counted as still open, so this may only overestimate the depth.
+#[cfg(feature = "std")]
+fn nesting_depth_exceeds(text: &str, limit: usize) -> bool {
+  const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr"
+  ];
+  const RAW_TEXT_ELEMENTS: &[&str] = &[
+    "iframe", "noembed", "noframes", "noscript", "plaintext", "script", "style", "textarea", "title", "xmp"
+  ];
+
+  let mut open = Vec::<&str>::new();
+  let mut rest = text;
+  while let Some(i) = rest.find('<') {
+    rest = &rest[i + 1..];
+    if let Some(comment) = rest.strip_prefix("!--") {
+      rest = comment.find("-->").map_or("", |i| &comment[i + 3..]);
+      continue;
+    } else if rest.starts_with(['!', '?']) {
+      rest = rest.find('>').map_or("", |i| &rest[i + 1..]);
+      continue;
     };
 
-    let mut steam_mods = Vec::new();
-    let mut local_mods = Vec::new();
-    for mod_element in document.select(&SELECTOR_MOD_CONTAINER) {
-      let display_name = select_item_name(mod_element)?;
+    let (is_end_tag, tag) = match rest.strip_prefix('/') {
+      Some(tag) => (true, tag),
+      None => (false, rest)
+    };
 
-      match select_item_origin(mod_element)? {
-        "from-local" => {
-          local_mods.push(PresetLocalMod { display_name: display_name.to_owned() });
-        },
-        "from-steam" => {
-          let link = select_item_link(mod_element)?;
-          let id = get_steam_link_steam_workshop_id(link)
-            .ok_or_else(|| Error::InvalidItemLinkSteamWorkshop(link.to_owned()))?;
-          steam_mods.push(PresetSteamMod { display_name: display_name.to_owned(), id });
-        },
-        origin => {
-          return Err(Error::InvalidItemOriginValue(origin.to_owned()));
-        }
+    if !tag.starts_with(|c: char| c.is_ascii_alphabetic()) {
+      continue;
+    };
+
+    let name_len = tag.find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>').unwrap_or(tag.len());
+    let name = &tag[..name_len];
+    rest = skip_tag_attributes(&tag[name_len..]);
+
+    if is_end_tag {
+      if let Some(i) = open.iter().rposition(|open| open.eq_ignore_ascii_case(name)) {
+        open.truncate(i);
+      };
+    } else if !VOID_ELEMENTS.iter().any(|void| void.eq_ignore_ascii_case(name)) {
+      if RAW_TEXT_ELEMENTS.iter().any(|raw| raw.eq_ignore_ascii_case(name)) {
+        rest = skip_raw_text(rest, name);
+        continue;
+      };
+
+      open.push(name);
+      if open.len() > limit {
+        return true;
       };
     };
+  };
 
-    let mut dlcs = Vec::new();
-    for dlc_element in document.select(&SELECTOR_DLC_CONTAINER) {
-      let display_name = select_item_name(dlc_element)?;
-      let link = select_item_link(dlc_element)?;
-      let id = get_steam_link_steam_app_id(link)
-        .ok_or_else(|| Error::InvalidItemLinkSteamApp(link.to_owned()))?;
-      dlcs.push(PresetDlc { display_name: display_name.to_owned(), id });
+  false
+}
+
+/// Skips past the end of a tag, ignoring any `>` within quoted attribute values.
+#[cfg(feature = "std")]
+fn skip_tag_attributes(text: &str) -> &str {
+  let mut quote = None;
+  let mut after_equals = false;
+  for (i, c) in text.char_indices() {
+    match quote {
+      Some(q) if c == q => quote = None,
+      Some(..) => (),
+      None if c == '>' => return &text[i + 1..],
+      None if (c == '"' || c == '\'') && after_equals => quote = Some(c),
+      None => ()
     };
 
-    Ok(Preset {
-      game,
-      preset_name: preset_name.map(str::to_owned),
-      steam_mods,
-      local_mods,
-      dlcs
+    if !c.is_ascii_whitespace() {
+      after_equals = c == '=';
+    };
+  };
+
+  ""
+}
+
+/// Skips past the end tag closing the raw text element `name`.
+#[cfg(feature = "std")]
+fn skip_raw_text<'t>(text: &'t str, name: &str) -> &'t str {
+  let mut rest = text;
+  while let Some(i) = rest.find("</") {
+    rest = &rest[i + 2..];
+    if rest.get(..name.len()).is_some_and(|tag| tag.eq_ignore_ascii_case(name)) {
+      return skip_tag_attributes(&rest[name.len()..]);
+    };
+  };
+
+  ""
+}
+
+/// The parts of a preset document that parsing needs, such that the same rules apply
+/// whether they come from a parsed [`Html`] document or from a token stream.
+#[cfg(feature = "std")]
+trait PresetSource<'a> {
+  type Row: PresetRow<'a>;
+
+  /// The `name` and `content` of each `<meta>` tag in the document head.
+  fn meta_tags(&self) -> impl Iterator<Item = (&'a str, &'a str)>;
+
+  /// Describes the document head, for error messages.
+  fn head_snippet(&self) -> String;
+
+  /// Must yield rows in document order, as row indices and [`PresetSteamMod::order`] are assigned from it.
+  /// [`Html::select`] traverses the tree in order, as does the token stream.
+  fn mod_rows(&self) -> impl Iterator<Item = Self::Row>;
+
+  /// Must yield rows in document order, as with [`PresetSource::mod_rows`].
+  fn dlc_rows(&self) -> impl Iterator<Item = Self::Row>;
+
+  /// The text of each comment outside of the document body.
+  fn header_comments(&self) -> impl Iterator<Item = &'a str>;
+}
+
+/// A mod or DLC row of a preset document, see [`PresetSource`].
+#[cfg(feature = "std")]
+trait PresetRow<'a>: Copy {
+  /// The text of the display name cell.
+  fn name(self) -> Result<Cow<'a, str>, Error>;
+
+  /// The class of the origin span.
+  fn origin(self) -> Result<&'a str, Error>;
+
+  /// The `href` of the link.
+  fn link(self) -> Result<&'a str, Error>;
+}
+
+#[cfg(feature = "std")]
+impl<'a> PresetSource<'a> for &'a Html {
+  type Row = ElementRef<'a>;
+
+  fn meta_tags(&self) -> impl Iterator<Item = (&'a str, &'a str)> {
+    self.select(&SELECTOR_META)
+      .filter_map(|element| Option::zip(element.value().attr("name"), element.value().attr("content")))
+  }
+
+  fn head_snippet(&self) -> String {
+    document_snippet(self)
+  }
+
+  fn mod_rows(&self) -> impl Iterator<Item = Self::Row> {
+    self.select(&SELECTOR_MOD_CONTAINER)
+  }
+
+  fn dlc_rows(&self) -> impl Iterator<Item = Self::Row> {
+    self.select(&SELECTOR_DLC_CONTAINER)
+  }
+
+  fn header_comments(&self) -> impl Iterator<Item = &'a str> {
+    // Comments outside of the body are children of the document, `<html>` or `<head>`, so only
+    // those are visited, in document order, rather than checking the ancestors of every node.
+    let is_element = |name: &'static str| move |element: &ElementRef<'a>| element.value().name() == name;
+    self.tree.root().children()
+      .flat_map(move |node| {
+        let html = ElementRef::wrap(node).filter(is_element("html"));
+        std::iter::once(node).chain(html.into_iter().flat_map(move |html| {
+          html.children().flat_map(move |node| {
+            let head = ElementRef::wrap(node).filter(is_element("head"));
+            std::iter::once(node).chain(head.into_iter().flat_map(|head| head.children()))
+          })
+        }))
+      })
+      .filter_map(|node| node.value().as_comment())
+      .map(|comment| &**comment)
+  }
+}
+
+#[cfg(feature = "std")]
+impl<'a> PresetRow<'a> for ElementRef<'a> {
+  fn name(self) -> Result<Cow<'a, str>, Error> {
+    select_item_name(self)
+  }
+
+  fn origin(self) -> Result<&'a str, Error> {
+    select_item_origin(self)
+  }
+
+  fn link(self) -> Result<&'a str, Error> {
+    select_item_link(self)
+  }
+}
+
+/// When `document_text` is provided, row errors are given a [`SourceLocation`] within it.
+#[cfg(feature = "std")]
+fn parse_document<'a, S: PresetSource<'a>>(
+  document: S,
+  document_text: Option<&str>,
+  options: ParseOptions<'_>,
+  errors: Option<&mut Vec<Error>>,
+  warnings: Option<&mut Vec<Warning>>
+) -> Result<PresetRef<'a>, Error> {
+  let (game, kind) = match select_preset_type(&document, options.best_effort) {
+    Err(Error::NotAPreset) if options.fragments && document.mod_rows().chain(document.dlc_rows()).next().is_some() => {
+      (Game::Arma, PresetKind::Preset)
+    },
+    result => result?
+  };
+
+  let preset_name = select_preset_name(&document, game);
+
+  let rows = parse_rows(document.mod_rows(), document.dlc_rows(), document_text, options, errors, warnings)?;
+  let extra_meta = select_extra_meta(&document, game);
+  let (generated_by, generated_at) = select_generation_info(&document);
+
+  Ok(PresetRef {
+    game,
+    kind,
+    preset_name,
+    steam_mods: rows.steam_mods,
+    local_mods: rows.local_mods,
+    dlcs: rows.dlcs,
+    extra_meta,
+    generated_by,
+    generated_at
+  })
+}
+
+/// The mods and DLCs parsed from the rows of a preset, see [`parse_rows`].
+#[cfg(feature = "std")]
+struct ParsedRows<'a> {
+  steam_mods: Vec<PresetSteamModRef<'a>>,
+  local_mods: Vec<PresetLocalModRef<'a>>,
+  dlcs: Vec<PresetDlcRef<'a>>
+}
+
+/// When `document_text` is provided, row errors are given a [`SourceLocation`] within it.
+#[cfg(feature = "std")]
+fn parse_rows<'a, R: PresetRow<'a>>(
+  mod_rows: impl Iterator<Item = R>,
+  dlc_rows: impl Iterator<Item = R>,
+  document_text: Option<&str>,
+  options: ParseOptions<'_>,
+  mut errors: Option<&mut Vec<Error>>,
+  mut warnings: Option<&mut Vec<Warning>>
+) -> Result<ParsedRows<'a>, Error> {
+  let mut steam_mods = Vec::new();
+  let mut local_mods = Vec::new();
+  let mut row_warnings = Vec::new();
+  let mut mod_locator = document_text.map(|text| SourceLocator::new(text, "ModContainer"));
+  for (index, mod_element) in mod_rows.enumerate() {
+    row_warnings.clear();
+    match parse_mod_row(mod_element, index, options, &mut row_warnings) {
+      Ok(ParsedMod::Steam(steam_mod)) => steam_mods.push(steam_mod),
+      Ok(ParsedMod::Local(local_mod)) => local_mods.push(local_mod),
+      Err(err) => {
+        let location = mod_locator.as_mut().and_then(|locator| locator.locate(index));
+        let err = err.with_location(location);
+        match errors.as_deref_mut() {
+          Some(errors) => errors.push(Error::InvalidModRow { index, source: Box::new(err) }),
+          None => return Err(err)
+        };
+
+        continue;
+      }
+    };
+
+    if let Some(warnings) = warnings.as_deref_mut() {
+      warnings.append(&mut row_warnings);
+    };
+  };
+
+  let mut dlcs = Vec::new();
+  let mut dlc_locator = document_text.map(|text| SourceLocator::new(text, "DlcContainer"));
+  for (index, dlc_element) in dlc_rows.enumerate() {
+    row_warnings.clear();
+    match parse_dlc_row(dlc_element, index, options, &mut row_warnings) {
+      Ok(dlc) => dlcs.push(dlc),
+      Err(err) => {
+        let location = dlc_locator.as_mut().and_then(|locator| locator.locate(index));
+        let err = err.with_location(location);
+        match errors.as_deref_mut() {
+          Some(errors) => errors.push(Error::InvalidDlcRow { index, source: Box::new(err) }),
+          None => return Err(err)
+        };
+
+        continue;
+      }
+    };
+
+    if let Some(warnings) = warnings.as_deref_mut() {
+      warnings.append(&mut row_warnings);
+    };
+  };
+
+  if options.strict_ids {
+    let mut seen = HashSet::new();
+    if let Some(m) = steam_mods.iter().find(|m| !seen.insert(m.id)) {
+      return Err(Error::DuplicateSteamId(m.id));
+    };
+
+    let mut seen = HashSet::new();
+    if let Some(m) = dlcs.iter().find(|m| !seen.insert(m.id)) {
+      return Err(Error::DuplicateDlcId(m.id));
+    };
+  };
+
+  Ok(ParsedRows { steam_mods, local_mods, dlcs })
+}
+
+#[cfg(feature = "std")]
+fn parse_mod_row<'a, R: PresetRow<'a>>(
+  mod_element: R,
+  order: usize,
+  options: ParseOptions<'_>,
+  warnings: &mut Vec<Warning>
+) -> Result<ParsedMod<'a>, Error> {
+  let display_name = mod_element.name()?;
+  let display_name = match options.normalize_names {
+    true => normalize_item_name(display_name, |original, normalized| {
+      warnings.push(Warning::NormalizedModName { index: order, original, normalized });
+    }),
+    false => display_name
+  };
+
+  let origin = mod_element.origin().or_else(|err| match options.infer_origin {
+    true => {
+      let origin = infer_item_origin(mod_element.link(), options.hosts).ok_or(err)?;
+      warnings.push(Warning::InferredOrigin { index: order, origin: origin.to_owned() });
+      Ok(origin)
+    },
+    false => Err(err)
+  });
+
+  match origin? {
+    "from-local" => {
+      Ok(ParsedMod::Local(PresetLocalModRef { display_name, order }))
+    },
+    "from-steam" => {
+      let link = mod_element.link()?;
+      let id = get_steam_link_steam_workshop_id(link, options.hosts)
+        .ok_or_else(|| Error::InvalidItemLinkSteamWorkshop(link.to_owned(), None))?;
+      if has_extra_query_params(link, "id") {
+        warnings.push(Warning::ExtraQueryParams { index: order, link: link.trim().to_owned() });
+      };
+
+      Ok(ParsedMod::Steam(PresetSteamModRef { display_name, id, order }))
+    },
+    origin => {
+      Err(Error::InvalidItemOriginValue(origin.to_owned(), None))
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+fn parse_dlc_row<'a, R: PresetRow<'a>>(
+  dlc_element: R,
+  index: usize,
+  options: ParseOptions<'_>,
+  warnings: &mut Vec<Warning>
+) -> Result<PresetDlcRef<'a>, Error> {
+  let display_name = dlc_element.name()?;
+  let display_name = match options.normalize_names {
+    true => normalize_item_name(display_name, |original, normalized| {
+      warnings.push(Warning::NormalizedDlcName { index, original, normalized });
+    }),
+    false => display_name
+  };
+  let link = dlc_element.link()?;
+  let id = get_steam_link_steam_app_id(link, options.hosts)
+    .ok_or_else(|| Error::InvalidItemLinkSteamApp(link.to_owned(), None))?;
+  Ok(PresetDlcRef { display_name, id })
+}
+
+#[cfg(feature = "std")]
+fn select_preset_type<'a, S: PresetSource<'a>>(document: &S, best_effort: bool) -> Result<(Game, PresetKind), Error> {
+  let is_preset = [Game::Arma, Game::DayZ].into_iter().any(|game| {
+    select_meta(document, game.meta_namespace(), "Type").is_some() ||
+    select_preset_name(document, game).is_some()
+  });
+
+  if !is_preset {
+    return Err(Error::NotAPreset);
+  };
+
+  let [arma, dayz] = [Game::Arma, Game::DayZ].map(|game| {
+    match select_meta(document, game.meta_namespace(), "Type") {
+      Some(content) => match PresetKind::from_type_value(content) {
+        Some(kind) => Ok((game, kind)),
+        None if best_effort => Ok((game, PresetKind::Preset)),
+        None => Err(Error::InvalidPresetTypeValue(content.to_owned()))
+      },
+      None if best_effort && select_preset_name(document, game).is_some() => Ok((game, PresetKind::Preset)),
+      None => Err(Error::SelectorFailedPresetType(document.head_snippet()))
+    }
+  });
+
+  Result::or(arma, dayz)
+}
+
+#[cfg(feature = "std")]
+fn select_preset_name<'a, S: PresetSource<'a>>(document: &S, game: Game) -> Option<&'a str> {
+  select_meta(document, game.meta_namespace(), "PresetName")
+}
+
+/// Collects the name and content of every `<meta>` tag other than the preset type and name tags of `game`.
+#[cfg(feature = "std")]
+fn select_extra_meta<'a, S: PresetSource<'a>>(document: &S, game: Game) -> Vec<(&'a str, &'a str)> {
+  let namespace = game.meta_namespace();
+  document.meta_tags()
+    .filter(|(meta_name, _)| match meta_name.split_once(':') {
+      Some((meta_namespace, meta_name)) => !meta_namespace.eq_ignore_ascii_case(namespace) ||
+        !(meta_name.eq_ignore_ascii_case("Type") || meta_name.eq_ignore_ascii_case("PresetName")),
+      None => true
+    })
+    .collect()
+}
+
+/// Finds the tool that generated the document and when, from header comments like the
+/// `Created by Arma 3 Launcher: https://arma3.com` comment written by the launcher, falling back
+/// to the standard `generator` meta tag for the tool. This is best-effort, as the format varies.
+#[cfg(feature = "std")]
+fn select_generation_info<'a, S: PresetSource<'a>>(document: &S) -> (Option<&'a str>, Option<&'a str>) {
+  fn strip_prefixes<'t>(line: &'t str, prefixes: &[&str]) -> Option<&'t str> {
+    prefixes.iter().find_map(|prefix| {
+      line.get(..prefix.len())
+        .filter(|start| start.eq_ignore_ascii_case(prefix))
+        .map(|_| line[prefix.len()..].trim())
+    })
+  }
+
+  let (mut generated_by, mut generated_at) = (None, None);
+  for line in document.header_comments().flat_map(str::lines).map(str::trim) {
+    if let Some(by) = strip_prefixes(line, &["created by ", "generated by "]) {
+      generated_by = generated_by.or(Some(by).filter(|by| !by.is_empty()));
+    } else if let Some(at) = strip_prefixes(line, &["created at ", "created on ", "generated at ", "generated on "]) {
+      generated_at = generated_at.or(Some(at).filter(|at| !at.is_empty()));
+    };
+  };
+
+  let generated_by = generated_by.or_else(|| {
+    document.meta_tags()
+      .find(|(meta_name, _)| meta_name.eq_ignore_ascii_case("generator"))
+      .map(|(_, content)| content)
+  });
+
+  (generated_by, generated_at)
+}
+
+/// Finds the content of the `<meta>` tag named `namespace:name`, ignoring ASCII case.
+#[cfg(feature = "std")]
+fn select_meta<'a, S: PresetSource<'a>>(document: &S, namespace: &str, name: &str) -> Option<&'a str> {
+  document.meta_tags()
+    .find(|(meta_name, _)| {
+      meta_name.split_once(':')
+        .is_some_and(|(meta_namespace, meta_name)| {
+          meta_namespace.eq_ignore_ascii_case(namespace) && meta_name.eq_ignore_ascii_case(name)
+        })
     })
+    .map(|(_, content)| content)
+}
+
+#[cfg(feature = "std")]
+fn select_item_name(element: ElementRef<'_>) -> Result<Cow<'_, str>, Error> {
+  element.select(&SELECTOR_ITEM_NAME).next()
+    .map(select_text)
+    .ok_or_else(|| Error::SelectorFailedItemName(element_snippet(element), None))
+}
+
+/// Collapses the whitespace in `name`, calling `on_change` with the original and normalized names if that changed it.
+#[cfg(feature = "std")]
+fn normalize_item_name<F>(name: Cow<'_, str>, on_change: F) -> Cow<'_, str>
+where F: FnOnce(String, String) {
+  let normalized = collapse_whitespace(name.clone());
+  if normalized != name {
+    on_change(name.into_owned(), normalized.clone().into_owned());
+  };
+
+  normalized
+}
+
+/// Trims `text` and collapses each run of whitespace within it into a single space,
+/// only allocating if `text` is not already in that form.
+fn collapse_whitespace(text: Cow<'_, str>) -> Cow<'_, str> {
+  let is_collapsed = !text.starts_with(char::is_whitespace) && !text.ends_with(char::is_whitespace) &&
+    !text.contains(|c: char| c.is_whitespace() && c != ' ') && !text.contains("  ");
+  if is_collapsed {
+    text
+  } else {
+    Cow::Owned(text.split_whitespace().collect::<Vec<&str>>().join(" "))
   }
 }
 
-const STEAM_WORKSHOP_LINK: &str = "steamcommunity.com/sharedfiles/filedetails/?id=";
+/// Joins all text nodes within `element`, only allocating if there is more than one.
+#[cfg(feature = "std")]
+fn select_text(element: ElementRef<'_>) -> Cow<'_, str> {
+  let mut text = element.text();
+  match (text.next(), text.next()) {
+    (None, _) => Cow::Borrowed(""),
+    (Some(first), None) => Cow::Borrowed(first),
+    (Some(first), Some(second)) => Cow::Owned([first, second].into_iter().chain(text).collect())
+  }
+}
+
+#[cfg(feature = "std")]
+fn select_item_link(element: ElementRef<'_>) -> Result<&str, Error> {
+  element.select(&SELECTOR_ITEM_LINK).next()
+    .and_then(|element| element.value().attr("href"))
+    .ok_or_else(|| Error::SelectorFailedItemLink(element_snippet(element), None))
+}
+
+#[cfg(feature = "std")]
+fn select_item_origin(element: ElementRef<'_>) -> Result<&str, Error> {
+  element.select(&SELECTOR_ITEM_ORIGIN).next()
+    .and_then(|element| element.value().attr("class"))
+    .ok_or_else(|| Error::SelectorFailedItemOrigin(element_snippet(element), None))
+}
+
+/// Infers the origin of a mod row without an origin span from its link:
+/// a Steam Workshop link implies `from-steam`, and no link at all implies `from-local`.
+#[cfg(feature = "std")]
+fn infer_item_origin(link: Result<&str, Error>, hosts: Option<&SteamHosts>) -> Option<&'static str> {
+  match link {
+    Ok(link) => get_steam_link_steam_workshop_id(link, hosts).map(|_| "from-steam"),
+    Err(..) => Some("from-local")
+  }
+}
+
+#[cfg(feature = "std")]
+const SNIPPET_MAX_LEN: usize = 160;
+
+/// Returns the outer HTML of the document's `<head>`, truncated, to keep error messages bounded.
+#[cfg(feature = "std")]
+fn document_snippet(document: &Html) -> String {
+  document.select(&SELECTOR_HEAD).next()
+    .map_or_else(|| "<head> not found".to_owned(), element_snippet)
+}
+
+#[cfg(feature = "std")]
+fn element_snippet(element: ElementRef<'_>) -> String {
+  let mut html = element.html();
+  if let Some((i, _)) = html.char_indices().nth(SNIPPET_MAX_LEN) {
+    html.truncate(i);
+    html.push_str("...");
+  };
+
+  html
+}
+
+/// Normalizes a display name for the purposes of matching local mods by name.
+fn normalize_name(name: &str) -> String {
+  name.trim().to_ascii_lowercase()
+}
+
+/// The URL of a Steam Workshop item, without the trailing item id.
+pub const STEAM_WORKSHOP_URL: &str = "https://steamcommunity.com/sharedfiles/filedetails/?id=";
+/// The URL of a Steam Store app page, without the trailing app id.
+pub const STEAM_STORE_APP_URL: &str = "https://store.steampowered.com/app/";
+
+#[cfg(feature = "std")]
+const STEAM_WORKSHOP_PATH: &str = "steamcommunity.com/sharedfiles/filedetails";
+#[cfg(feature = "std")]
 const STEAM_APP_LINK: &str = "store.steampowered.com/app/";
 
-fn get_steam_link_steam_workshop_id(link: &str) -> Option<u64> {
+/// Accepts links under the real Steam hosts, and under `hosts` when provided.
+#[cfg(feature = "std")]
+fn get_steam_link_steam_workshop_id(link: &str, hosts: Option<&SteamHosts>) -> Option<WorkshopId> {
   strip_url_protocol(link)
-    .and_then(|link| link.strip_prefix(STEAM_WORKSHOP_LINK))
-    .and_then(|id| id.parse::<u64>().ok())
+    .map(strip_url_www)
+    .and_then(|link| {
+      link.strip_prefix(STEAM_WORKSHOP_PATH).or_else(|| {
+        link.strip_prefix(hosts?.workshop.as_str())?.strip_prefix("/sharedfiles/filedetails")
+      })
+    })
+    .and_then(|link| link.strip_prefix("/?").or_else(|| link.strip_prefix('?')))
+    .and_then(|query| get_query_param(query, "id"))
+    .and_then(|id| id.parse::<WorkshopId>().ok())
 }
 
-fn get_steam_link_steam_app_id(link: &str) -> Option<u64> {
+/// Accepts links under the real Steam hosts, and under `hosts` when provided.
+#[cfg(feature = "std")]
+fn get_steam_link_steam_app_id(link: &str, hosts: Option<&SteamHosts>) -> Option<AppId> {
   strip_url_protocol(link)
-    .and_then(|link| link.strip_prefix(STEAM_APP_LINK))
-    .and_then(|id| id.parse::<u64>().ok())
+    .map(strip_url_www)
+    .and_then(|link| {
+      link.strip_prefix(STEAM_APP_LINK).or_else(|| {
+        link.strip_prefix(hosts?.store.as_str())?.strip_prefix("/app/")
+      })
+    })
+    .map(|link| link.find(|c: char| !c.is_ascii_digit()).map_or(link, |end| &link[..end]))
+    .and_then(|id| id.parse::<AppId>().ok())
+}
+
+/// Returns true if the query of `link` has any parameters other than `key`.
+#[cfg(feature = "std")]
+fn has_extra_query_params(link: &str, key: &str) -> bool {
+  link.trim().split_once('?').is_some_and(|(_, query)| {
+    let query = query.split_once('#').map_or(query, |(query, _)| query);
+    query.split('&').any(|pair| pair.split_once('=').map_or(pair, |(k, _)| k) != key)
+  })
+}
+
+#[cfg(feature = "std")]
+fn get_query_param<'a>(query: &'a str, key: &str) -> Option<&'a str> {
+  let query = query.split_once('#').map_or(query, |(query, _)| query);
+  query.split('&').find_map(|pair| match pair.split_once('=') {
+    Some((k, v)) if k == key => Some(v),
+    _ => None
+  })
 }
 
+#[cfg(feature = "std")]
 fn strip_url_protocol(link: &str) -> Option<&str> {
   let link = link.trim();
   Option::or(
     link.strip_prefix("https://"),
     link.strip_prefix("http://")
-  )
+  ).or_else(|| link.strip_prefix("//"))
+}
+
+#[cfg(feature = "std")]
+fn strip_url_www(link: &str) -> &str {
+  link.strip_prefix("www.").unwrap_or(link)
+}
+
+/// Escapes `text` for use within a quoted HTML attribute value, escaping `&`, `<`, `>`, `"` and `'`.
+fn escape_attr(text: &str) -> Escape<'_> {
+  Escape { text, quotes: true }
+}
+
+/// Escapes `text` for use as HTML element content, escaping `&`, `<` and `>`.
+fn escape_text(text: &str) -> Escape<'_> {
+  Escape { text, quotes: false }
+}
+
+/// Returns true if `text` can be written within an HTML comment on a single line and read back unchanged,
+/// since comments have no escapes.
+fn is_comment_safe(text: &str) -> bool {
+  !text.contains("--") && !text.ends_with('-') && !text.contains(['\n', '\r']) && text.trim() == text
+}
+
+struct Escape<'a> {
+  text: &'a str,
+  quotes: bool
+}
+
+impl fmt::Display for Escape<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut last = 0;
+    for (i, c) in self.text.char_indices() {
+      let escaped = match c {
+        '&' => "&amp;",
+        '<' => "&lt;",
+        '>' => "&gt;",
+        '"' if self.quotes => "&quot;",
+        '\'' if self.quotes => "&#39;",
+        _ => continue
+      };
+
+      f.write_str(&self.text[last..i])?;
+      f.write_str(escaped)?;
+      last = i + c.len_utf8();
+    };
+
+    f.write_str(&self.text[last..])
+  }
 }
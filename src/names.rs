@@ -0,0 +1,112 @@
+use crate::{normalize_name, ModRef};
+
+use std::collections::HashMap;
+
+/// How display names are normalized for matching, as when deduping, diffing or looking up local mods by name.
+///
+/// Display names themselves are never changed, only the keys they are compared by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NameNormalization {
+  /// Trim and ASCII lowercase names, the default everywhere names are matched.
+  #[default]
+  AsciiLowercase,
+  /// Like [`NameNormalization::AsciiLowercase`], but also strip diacritics, so `Café` matches `cafe`.
+  ///
+  /// Names are decomposed to Unicode NFD with their combining marks removed.
+  /// Requires the `unicode-normalization` feature.
+  #[cfg(feature = "unicode-normalization")]
+  FoldAccents
+}
+
+impl NameNormalization {
+  /// The key `name` is matched by.
+  pub fn normalize(self, name: &str) -> String {
+    match self {
+      NameNormalization::AsciiLowercase => normalize_name(name),
+      #[cfg(feature = "unicode-normalization")]
+      NameNormalization::FoldAccents => {
+        use unicode_normalization::UnicodeNormalization;
+        use unicode_normalization::char::is_combining_mark;
+
+        let mut name = name.trim().nfd().filter(|&c| !is_combining_mark(c)).collect::<String>();
+        name.make_ascii_lowercase();
+        name
+      }
+    }
+  }
+}
+
+/// A mapping from mod display names to addon folder names, for exporters that need folder names,
+/// see [`Preset::to_mod_param_mapped`][crate::Preset::to_mod_param_mapped] and
+/// [`Preset::to_arma3sync_modset_mapped`][crate::Preset::to_arma3sync_modset_mapped].
+///
+/// Names are matched by normalized display name. Mods without an entry fall back to `@<id>` for
+/// Steam mods, and to a folder-safe version of their display name for local mods, see [`ModNameMap::folder_name`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModNameMap {
+  folder_names: HashMap<String, String>
+}
+
+impl ModNameMap {
+  /// Creates an empty map, where every mod gets its default folder name.
+  pub fn new() -> Self {
+    ModNameMap::default()
+  }
+
+  /// Creates a map from `(display_name, folder_name)` pairs. Later pairs replace earlier ones with the same name.
+  pub fn from_pairs<I, K, V>(pairs: I) -> Self
+  where I: IntoIterator<Item = (K, V)>, K: AsRef<str>, V: Into<String> {
+    let folder_names = pairs.into_iter()
+      .map(|(display_name, folder_name)| (normalize_name(display_name.as_ref()), folder_name.into()))
+      .collect();
+    ModNameMap { folder_names }
+  }
+
+  /// Maps `display_name` to `folder_name`, returning the folder name it was previously mapped to, if any.
+  pub fn insert(&mut self, display_name: &str, folder_name: impl Into<String>) -> Option<String> {
+    self.folder_names.insert(normalize_name(display_name), folder_name.into())
+  }
+
+  /// The folder name explicitly mapped to `display_name`, if any.
+  pub fn get(&self, display_name: &str) -> Option<&str> {
+    self.folder_names.get(&normalize_name(display_name)).map(String::as_str)
+  }
+
+  /// The folder name for `m`, either as mapped, or failing that, `@<id>` for Steam mods
+  /// and a folder-safe version of the display name for local mods.
+  ///
+  /// A folder-safe name has whitespace replaced with underscores, any character other than an ASCII
+  /// letter, digit, `_`, `-` or `.` removed, and is prefixed with `@` if it was not already.
+  /// A name left with no characters this way, such as an empty or entirely non-ASCII name, instead becomes
+  /// `@local_` followed by the hex encoded UTF-8 bytes of the trimmed name, so that distinct names keep distinct folders.
+  pub fn folder_name(&self, m: ModRef<'_>) -> String {
+    if let Some(folder_name) = self.get(m.display_name()) {
+      return folder_name.to_owned();
+    };
+
+    match m {
+      ModRef::Steam(m) => format!("@{}", m.id),
+      ModRef::Local(m) => folder_safe_name(&m.display_name)
+    }
+  }
+}
+
+fn folder_safe_name(display_name: &str) -> String {
+  let mut folder_name = String::from("@");
+  for c in display_name.trim().trim_start_matches('@').chars() {
+    if c.is_whitespace() {
+      folder_name.push('_');
+    } else if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') {
+      folder_name.push(c);
+    };
+  };
+
+  if folder_name == "@" {
+    folder_name.push_str("local_");
+    for b in display_name.trim().bytes() {
+      folder_name.push_str(&format!("{b:02x}"));
+    };
+  };
+
+  folder_name
+}
@@ -0,0 +1,453 @@
+use crate::{collapse_whitespace, normalize_name, AppId, Error, Game, NameNormalization, Preset, PresetDlc, PresetLocalMod, PresetSteamMod, WorkshopId};
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+/// Which display name to keep when merging a Steam mod or DLC that is already present,
+/// see [`Preset::merge_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NameResolution {
+  /// Keep the name seen first, the one already in the preset being merged into.
+  #[default]
+  FirstSeen,
+  /// Keep the name with the most characters, preferring the name seen first when tied.
+  Longest,
+  /// Keep the name that sorts first lexicographically.
+  Lexicographically
+}
+
+/// Options for [`Preset::merge_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MergeOptions {
+  /// Which display name to keep for a Steam mod or DLC that is already present, see [`NameResolution`].
+  pub resolution: NameResolution,
+  /// How display names are normalized for matching local mods, see [`NameNormalization`].
+  pub normalization: NameNormalization
+}
+
+impl NameResolution {
+  fn prefers(self, new: &str, old: &str) -> bool {
+    match self {
+      NameResolution::FirstSeen => false,
+      NameResolution::Longest => new.chars().count() > old.chars().count(),
+      NameResolution::Lexicographically => new < old
+    }
+  }
+}
+
+impl Preset {
+  /// Returns the game of both this preset and `other`, or fails with [`Error::GameMismatch`]
+  /// if they are not for the same game. Operations combining two presets check this first.
+  pub fn assert_same_game(&self, other: &Preset) -> Result<Game, Error> {
+    match self.game == other.game {
+      true => Ok(self.game),
+      false => Err(Error::GameMismatch { a: self.game, b: other.game })
+    }
+  }
+
+  /// Appends the Steam mods, local mods and DLCs from `other` that are not already in this preset.
+  ///
+  /// Steam mods and DLCs are matched by id, local mods by normalized display name.
+  /// When an item is present in both presets, the entry already in this preset is kept.
+  /// Mods taken from `other` have their [`order`][crate::PresetSteamMod::order] cleared,
+  /// since it refers to a different document.
+  /// Fails with [`Error::GameMismatch`] if the presets are not for the same game.
+  pub fn merge(&mut self, other: &Preset) -> Result<(), Error> {
+    self.merge_with(other, NameResolution::FirstSeen)
+  }
+
+  /// Like [`Preset::merge`], but when a Steam mod or DLC from `other` is already present,
+  /// the display name to keep is picked by `resolution`. Local mods are matched by name, so they are merged as usual.
+  pub fn merge_with(&mut self, other: &Preset, resolution: NameResolution) -> Result<(), Error> {
+    self.merge_with_options(other, MergeOptions { resolution, ..MergeOptions::default() })
+  }
+
+  /// Like [`Preset::merge`], as configured by `options`.
+  pub fn merge_with_options(&mut self, other: &Preset, options: MergeOptions) -> Result<(), Error> {
+    self.assert_same_game(other)?;
+
+    let MergeOptions { resolution, normalization } = options;
+    let mut steam_ids = self.steam_mods.iter().enumerate().map(|(i, m)| (m.id, i)).collect::<HashMap<WorkshopId, usize>>();
+    let mut dlc_ids = self.dlcs.iter().enumerate().map(|(i, m)| (m.id, i)).collect::<HashMap<AppId, usize>>();
+    let mut local_names = self.local_mods.iter().map(|m| normalization.normalize(&m.display_name)).collect::<HashSet<String>>();
+
+    for m in other.steam_mods.iter() {
+      match steam_ids.get(&m.id) {
+        Some(&i) => if resolution.prefers(&m.display_name, &self.steam_mods[i].display_name) {
+          self.steam_mods[i].display_name.clone_from(&m.display_name);
+        },
+        None => {
+          steam_ids.insert(m.id, self.steam_mods.len());
+          self.steam_mods.push(PresetSteamMod { order: None, ..m.clone() });
+        }
+      };
+    };
+
+    for m in other.dlcs.iter() {
+      match dlc_ids.get(&m.id) {
+        Some(&i) => if resolution.prefers(&m.display_name, &self.dlcs[i].display_name) {
+          self.dlcs[i].display_name.clone_from(&m.display_name);
+        },
+        None => {
+          dlc_ids.insert(m.id, self.dlcs.len());
+          self.dlcs.push(m.clone());
+        }
+      };
+    };
+
+    self.local_mods.extend(other.local_mods.iter().filter(|m| local_names.insert(normalization.normalize(&m.display_name)))
+      .map(|m| PresetLocalMod { order: None, ..m.clone() }));
+
+    Ok(())
+  }
+
+  /// Merges each preset from `others` into this preset in turn, see [`Preset::merge`].
+  ///
+  /// Stops at the first preset for a different game, leaving any presets before it merged.
+  pub fn merge_all<'p, I>(&mut self, others: I) -> Result<(), Error>
+  where I: IntoIterator<Item = &'p Preset> {
+    others.into_iter().try_for_each(|other| self.merge(other))
+  }
+
+  /// Removes Steam mods whose id already appeared earlier in the list, returning how many were removed.
+  pub fn dedupe_steam_mods(&mut self) -> usize {
+    let mut seen = HashSet::new();
+    let len = self.steam_mods.len();
+    self.steam_mods.retain(|m| seen.insert(m.id));
+    len - self.steam_mods.len()
+  }
+
+  /// Removes DLCs whose id already appeared earlier in the list, returning how many were removed.
+  pub fn dedupe_dlcs(&mut self) -> usize {
+    let mut seen = HashSet::new();
+    let len = self.dlcs.len();
+    self.dlcs.retain(|m| seen.insert(m.id));
+    len - self.dlcs.len()
+  }
+
+  /// Removes local mods whose normalized display name already appeared earlier in the list,
+  /// returning how many were removed.
+  pub fn dedupe_local_mods(&mut self) -> usize {
+    self.dedupe_local_mods_with(NameNormalization::default())
+  }
+
+  /// Like [`Preset::dedupe_local_mods`], but with names compared as normalized by `normalization`.
+  pub fn dedupe_local_mods_with(&mut self, normalization: NameNormalization) -> usize {
+    let mut seen = HashSet::new();
+    let len = self.local_mods.len();
+    self.local_mods.retain(|m| seen.insert(normalization.normalize(&m.display_name)));
+    len - self.local_mods.len()
+  }
+
+  /// Dedupes Steam mods, DLCs and local mods, returning the total number of entries removed.
+  pub fn dedupe_all(&mut self) -> usize {
+    self.dedupe_steam_mods() + self.dedupe_dlcs() + self.dedupe_local_mods()
+  }
+
+  /// Sorts Steam mods, DLCs and local mods by case-insensitive display name.
+  ///
+  /// The sort is stable, entries with equal keys keep their original relative order.
+  /// This clears the [`order`][crate::PresetSteamMod::order] of all mods, as it no longer applies.
+  pub fn sort_by_name(&mut self) {
+    self.clear_order();
+    self.steam_mods.sort_by_cached_key(|m| normalize_name(&m.display_name));
+    self.dlcs.sort_by_cached_key(|m| normalize_name(&m.display_name));
+    self.local_mods.sort_by_cached_key(|m| normalize_name(&m.display_name));
+  }
+
+  /// Sorts Steam mods and DLCs by id, and local mods (which have no id) by case-insensitive display name.
+  ///
+  /// The sort is stable, entries with equal keys keep their original relative order.
+  /// This clears the [`order`][crate::PresetSteamMod::order] of all mods, as it no longer applies.
+  pub fn sort_by_id(&mut self) {
+    self.clear_order();
+    self.steam_mods.sort_by_key(|m| m.id);
+    self.dlcs.sort_by_key(|m| m.id);
+    self.local_mods.sort_by_cached_key(|m| normalize_name(&m.display_name));
+  }
+
+  /// Keeps only the Steam mods for which `f` returns true, as with [`Vec::retain`].
+  pub fn retain_steam_mods<F: FnMut(&PresetSteamMod) -> bool>(&mut self, f: F) {
+    self.steam_mods.retain(f);
+  }
+
+  /// Keeps only the local mods for which `f` returns true, as with [`Vec::retain`].
+  pub fn retain_local_mods<F: FnMut(&PresetLocalMod) -> bool>(&mut self, f: F) {
+    self.local_mods.retain(f);
+  }
+
+  /// Keeps only the DLCs for which `f` returns true, as with [`Vec::retain`].
+  pub fn retain_dlcs<F: FnMut(&PresetDlc) -> bool>(&mut self, f: F) {
+    self.dlcs.retain(f);
+  }
+
+  /// Keeps only the Steam mods whose id is in `ids`, local mods and DLCs are left untouched.
+  pub fn retain_steam_mods_by_id<I>(&mut self, ids: I)
+  where I: IntoIterator, I::Item: Into<WorkshopId> {
+    let ids = ids.into_iter().map(Into::into).collect::<HashSet<WorkshopId>>();
+    self.steam_mods.retain(|m| ids.contains(&m.id));
+  }
+
+  /// Keeps only the DLCs whose id is in `ids`, mods are left untouched.
+  pub fn retain_dlcs_by_id<I>(&mut self, ids: I)
+  where I: IntoIterator, I::Item: Into<AppId> {
+    let ids = ids.into_iter().map(Into::into).collect::<HashSet<AppId>>();
+    self.dlcs.retain(|m| ids.contains(&m.id));
+  }
+
+  /// Keeps only the Steam mods and DLCs whose id is in `ids`, local mods are left untouched.
+  ///
+  /// Ids are plain numbers matched against both workshop ids and app ids, see
+  /// [`Preset::retain_steam_mods_by_id`] and [`Preset::retain_dlcs_by_id`] to trim only one kind.
+  pub fn retain_by_id(&mut self, ids: &HashSet<u64>) {
+    self.steam_mods.retain(|m| ids.contains(&m.id.0));
+    self.dlcs.retain(|m| ids.contains(&m.id.0));
+  }
+
+  /// Moves local mods whose name is just a workshop id, such as `@450814997` or `450814997`,
+  /// to the Steam mods, returning how many were moved, see [`Preset::reclassify_local_as_steam_with`].
+  pub fn reclassify_local_as_steam(&mut self) -> usize {
+    self.reclassify_local_as_steam_with(|m| {
+      let name = m.display_name.trim();
+      let id = name.strip_prefix('@').unwrap_or(name);
+      Some(id).filter(|id| id.bytes().all(|b| b.is_ascii_digit())).and_then(|id| id.parse::<WorkshopId>().ok())
+    })
+  }
+
+  /// Moves each local mod for which `workshop_id` returns an id to the Steam mods, with that id,
+  /// returning how many were moved.
+  ///
+  /// Each moved mod keeps its display name and [`order`][crate::PresetSteamMod::order].
+  /// A mod whose id is already among the Steam mods is only removed.
+  pub fn reclassify_local_as_steam_with<F>(&mut self, mut workshop_id: F) -> usize
+  where F: FnMut(&PresetLocalMod) -> Option<WorkshopId> {
+    let len = self.local_mods.len();
+    self.local_mods.retain(|m| match workshop_id(m) {
+      Some(id) => {
+        if !self.steam_mods.iter().any(|m| m.id == id) {
+          self.steam_mods.push(PresetSteamMod { display_name: m.display_name.clone(), id, order: m.order });
+        };
+
+        false
+      },
+      None => true
+    });
+
+    len - self.local_mods.len()
+  }
+
+  /// Returns a new preset with the items that are in both this preset and `other`.
+  ///
+  /// Items are matched as in [`Preset::diff`], and taken from this preset. The result has no
+  /// `preset_name`, `extra_meta` nor generation info, since neither preset's would describe it. Fails with [`Error::GameMismatch`]
+  /// if the presets are not for the same game.
+  pub fn intersection(&self, other: &Preset) -> Result<Preset, Error> {
+    let diff = self.diff(other)?;
+    Ok(Preset {
+      game: self.game,
+      kind: self.kind,
+      preset_name: None,
+      steam_mods: diff.steam_shared.into_iter().cloned().collect(),
+      local_mods: diff.local_shared.into_iter().cloned().collect(),
+      dlcs: diff.dlc_shared.into_iter().cloned().collect(),
+      extra_meta: Vec::new(),
+      generated_by: None,
+      generated_at: None
+    })
+  }
+
+  /// Returns a new preset with the items that are in either this preset or `other`.
+  ///
+  /// Equivalent to merging `other` into a copy of this preset, see [`Preset::merge`].
+  /// The result has no `preset_name`, `extra_meta` nor generation info, as with [`Preset::intersection`].
+  pub fn union(&self, other: &Preset) -> Result<Preset, Error> {
+    let mut preset = Preset {
+      preset_name: None,
+      extra_meta: Vec::new(),
+      generated_by: None,
+      generated_at: None,
+      ..self.clone()
+    };
+    preset.merge(other)?;
+    Ok(preset)
+  }
+
+  /// Returns a new preset with the items that are in this preset but not in `other`.
+  ///
+  /// Items are matched as in [`Preset::diff`]. The result has no `preset_name`, `extra_meta` nor generation info,
+  /// as with [`Preset::intersection`].
+  pub fn difference(&self, other: &Preset) -> Result<Preset, Error> {
+    let diff = self.diff(other)?;
+    Ok(Preset {
+      game: self.game,
+      kind: self.kind,
+      preset_name: None,
+      steam_mods: diff.steam_only_a.into_iter().cloned().collect(),
+      local_mods: diff.local_only_a.into_iter().cloned().collect(),
+      dlcs: diff.dlc_only_a.into_iter().cloned().collect(),
+      extra_meta: Vec::new(),
+      generated_by: None,
+      generated_at: None
+    })
+  }
+
+  /// Returns a canonical copy of this preset, such that two presets with the same items in any
+  /// order and with any duplicates canonicalize identically, and so serialize identically.
+  ///
+  /// The rules are, in order:
+  /// 1. The preset name and all item names are trimmed, with each run of whitespace collapsed into a single space.
+  /// 2. Steam mods and DLCs are sorted by id then name, local mods by case-insensitive name then name.
+  /// 3. Duplicates are removed as in [`Preset::dedupe_all`], keeping the first entry of each after sorting.
+  /// 4. The [`order`][crate::PresetSteamMod::order] of all mods is cleared.
+  /// 5. Extra meta tags are sorted by name then content.
+  /// 6. [`generated_by`][Preset::generated_by] and [`generated_at`][Preset::generated_at] are cleared,
+  ///    since they describe when and how a file was exported rather than what it contains.
+  pub fn canonicalize(&self) -> Preset {
+    fn canonical_name(name: &str) -> String {
+      collapse_whitespace(Cow::Borrowed(name)).into_owned()
+    }
+
+    let mut preset = Preset {
+      game: self.game,
+      kind: self.kind,
+      preset_name: self.preset_name.as_deref().map(canonical_name),
+      steam_mods: self.steam_mods.iter()
+        .map(|m| PresetSteamMod { display_name: canonical_name(&m.display_name), id: m.id, order: None })
+        .collect(),
+      local_mods: self.local_mods.iter()
+        .map(|m| PresetLocalMod { display_name: canonical_name(&m.display_name), order: None })
+        .collect(),
+      dlcs: self.dlcs.iter()
+        .map(|m| PresetDlc { display_name: canonical_name(&m.display_name), id: m.id })
+        .collect(),
+      extra_meta: self.extra_meta.clone(),
+      generated_by: None,
+      generated_at: None
+    };
+
+    preset.steam_mods.sort_by(|a, b| Ord::cmp(&(a.id, &a.display_name), &(b.id, &b.display_name)));
+    preset.dlcs.sort_by(|a, b| Ord::cmp(&(a.id, &a.display_name), &(b.id, &b.display_name)));
+    preset.local_mods.sort_by_cached_key(|m| (normalize_name(&m.display_name), m.display_name.clone()));
+    preset.extra_meta.sort();
+    preset.dedupe_all();
+    preset
+  }
+
+  /// Splits this preset into one with only its Steam mods and DLCs, and one with only its local mods.
+  ///
+  /// Both halves keep the `game`, `kind`, `preset_name`, `extra_meta` and generation info of this preset.
+  pub fn split_by_origin(&self) -> (Preset, Preset) {
+    let steam = Preset {
+      game: self.game,
+      kind: self.kind,
+      preset_name: self.preset_name.clone(),
+      steam_mods: self.steam_mods.clone(),
+      local_mods: Vec::new(),
+      dlcs: self.dlcs.clone(),
+      extra_meta: self.extra_meta.clone(),
+      generated_by: self.generated_by.clone(),
+      generated_at: self.generated_at.clone()
+    };
+
+    let local = Preset {
+      game: self.game,
+      kind: self.kind,
+      preset_name: self.preset_name.clone(),
+      steam_mods: Vec::new(),
+      local_mods: self.local_mods.clone(),
+      dlcs: Vec::new(),
+      extra_meta: self.extra_meta.clone(),
+      generated_by: self.generated_by.clone(),
+      generated_at: self.generated_at.clone()
+    };
+
+    (steam, local)
+  }
+
+  /// Clears the [`order`][crate::PresetSteamMod::order] of all mods, such that they will be
+  /// serialized in the order of their collections rather than their original document order.
+  pub fn clear_order(&mut self) {
+    self.steam_mods.iter_mut().for_each(|m| m.order = None);
+    self.local_mods.iter_mut().for_each(|m| m.order = None);
+  }
+
+  /// Appends a Steam mod, even if one with the same id is already present, see [`Preset::add_steam_mod_unique`].
+  pub fn add_steam_mod(&mut self, id: impl Into<WorkshopId>, display_name: impl Into<String>) {
+    self.steam_mods.push(PresetSteamMod { display_name: display_name.into(), id: id.into(), order: None });
+  }
+
+  /// Appends a local mod, even if one with the same name is already present, see [`Preset::add_local_mod_unique`].
+  pub fn add_local_mod(&mut self, display_name: impl Into<String>) {
+    self.local_mods.push(PresetLocalMod { display_name: display_name.into(), order: None });
+  }
+
+  /// Appends a DLC, even if one with the same id is already present, see [`Preset::add_dlc_unique`].
+  pub fn add_dlc(&mut self, id: impl Into<AppId>, display_name: impl Into<String>) {
+    self.dlcs.push(PresetDlc { display_name: display_name.into(), id: id.into() });
+  }
+
+  /// Appends a Steam mod unless one with the same id is already present, returning whether it was added.
+  pub fn add_steam_mod_unique(&mut self, id: impl Into<WorkshopId>, display_name: impl Into<String>) -> bool {
+    let id = id.into();
+    let add = !self.contains_steam_id(id);
+    if add {
+      self.add_steam_mod(id, display_name);
+    };
+    add
+  }
+
+  /// Appends a local mod unless one with the same normalized name is already present, returning whether it was added.
+  pub fn add_local_mod_unique(&mut self, display_name: impl Into<String>) -> bool {
+    self.add_local_mod_unique_with(display_name, NameNormalization::default())
+  }
+
+  /// Like [`Preset::add_local_mod_unique`], but with names compared as normalized by `normalization`.
+  pub fn add_local_mod_unique_with(&mut self, display_name: impl Into<String>, normalization: NameNormalization) -> bool {
+    let display_name = display_name.into();
+    let name = normalization.normalize(&display_name);
+    let add = !self.local_mods.iter().any(|m| normalization.normalize(&m.display_name) == name);
+    if add {
+      self.add_local_mod(display_name);
+    };
+    add
+  }
+
+  /// Appends a DLC unless one with the same id is already present, returning whether it was added.
+  pub fn add_dlc_unique(&mut self, id: impl Into<AppId>, display_name: impl Into<String>) -> bool {
+    let id = id.into();
+    let add = !self.contains_dlc_id(id);
+    if add {
+      self.add_dlc(id, display_name);
+    };
+    add
+  }
+
+  /// Removes all Steam mods with this id, returning whether any were removed.
+  pub fn remove_steam_mod(&mut self, id: impl Into<WorkshopId>) -> bool {
+    let id = id.into();
+    let len = self.steam_mods.len();
+    self.steam_mods.retain(|m| m.id != id);
+    len != self.steam_mods.len()
+  }
+
+  /// Removes all local mods with this normalized name, returning whether any were removed.
+  pub fn remove_local_mod(&mut self, display_name: &str) -> bool {
+    self.remove_local_mod_with(display_name, NameNormalization::default())
+  }
+
+  /// Like [`Preset::remove_local_mod`], but with names compared as normalized by `normalization`.
+  pub fn remove_local_mod_with(&mut self, display_name: &str, normalization: NameNormalization) -> bool {
+    let name = normalization.normalize(display_name);
+    let len = self.local_mods.len();
+    self.local_mods.retain(|m| normalization.normalize(&m.display_name) != name);
+    len != self.local_mods.len()
+  }
+
+  /// Removes all DLCs with this id, returning whether any were removed.
+  pub fn remove_dlc(&mut self, id: impl Into<AppId>) -> bool {
+    let id = id.into();
+    let len = self.dlcs.len();
+    self.dlcs.retain(|m| m.id != id);
+    len != self.dlcs.len()
+  }
+}
@@ -0,0 +1,201 @@
+use crate::{parse_preset, Error, ParseOptions, Preset, SteamHosts};
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Something a parse recovered from without failing, see [`PresetParser::parse_with_warnings`].
+///
+/// Mod row indices count all mod rows, and DLC row indices all DLC rows, as in [`Error::InvalidModRow`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+  /// A mod row had no origin span, so its origin (`from-steam` or `from-local`) was inferred from its link.
+  InferredOrigin { index: usize, origin: String },
+  /// A mod row's name had surrounding or repeated whitespace, which was collapsed.
+  NormalizedModName { index: usize, original: String, normalized: String },
+  /// A DLC row's name had surrounding or repeated whitespace, which was collapsed.
+  NormalizedDlcName { index: usize, original: String, normalized: String },
+  /// A Steam mod row's workshop link had query parameters other than `id`, which were ignored.
+  ExtraQueryParams { index: usize, link: String }
+}
+
+impl fmt::Display for Warning {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Warning::InferredOrigin { index, origin } => {
+        write!(f, "mod row {index} has no origin, inferred {origin:?} from its link")
+      },
+      Warning::NormalizedModName { index, original, normalized } => {
+        write!(f, "mod row {index} name {original:?} was normalized to {normalized:?}")
+      },
+      Warning::NormalizedDlcName { index, original, normalized } => {
+        write!(f, "dlc row {index} name {original:?} was normalized to {normalized:?}")
+      },
+      Warning::ExtraQueryParams { index, link } => {
+        write!(f, "mod row {index} link {link:?} has extra query parameters")
+      }
+    }
+  }
+}
+
+/// The result of [`PresetParser::parse_with_warnings`].
+#[derive(Debug)]
+pub struct ParseOutcome {
+  pub preset: Preset,
+  /// Recoveries made while parsing, in document order, mod rows first.
+  pub warnings: Vec<Warning>,
+  /// Rows that failed to parse and were skipped, only ever non-empty when the parser is lenient.
+  pub errors: Vec<Error>
+}
+
+impl ParseOutcome {
+  /// Returns true if parsing did not need to recover from anything nor skip any rows.
+  pub fn is_clean(&self) -> bool {
+    self.warnings.is_empty() && self.errors.is_empty()
+  }
+}
+
+/// Diagnostics gathered while parsing a preset, see [`PresetParser::parse_with_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParseStats {
+  /// The number of mod rows in the document, including any that were skipped.
+  pub mod_rows: usize,
+  /// The number of DLC rows in the document, including any that were skipped.
+  pub dlc_rows: usize,
+  /// The number of mod and DLC rows that failed to parse and were skipped, only ever non-zero when the parser is lenient.
+  pub skipped_rows: usize,
+  /// The length of the document text in bytes.
+  pub document_bytes: usize,
+  /// How long parsing took.
+  pub elapsed: Duration
+}
+
+/// A configurable preset parser, for when the defaults of [`FromStr`][std::str::FromStr] do not fit.
+///
+/// `PresetParser::default()` parses exactly like [`FromStr`][std::str::FromStr].
+#[derive(Debug, Clone, Default)]
+pub struct PresetParser {
+  options: ParseOptions<'static>,
+  hosts: Option<SteamHosts>,
+  lenient: bool
+}
+
+impl PresetParser {
+  pub fn new() -> Self {
+    PresetParser::default()
+  }
+
+  /// Skip over mod or DLC rows that fail to parse instead of bailing, see [`Preset::parse_lenient`].
+  /// Defaults to `false`.
+  pub fn lenient(mut self, lenient: bool) -> Self {
+    self.lenient = lenient;
+    self
+  }
+
+  /// Accept any value for the preset type meta tag, or its absence when there is a preset name
+  /// meta tag, see [`Preset::parse_best_effort`].
+  /// Defaults to `false`.
+  pub fn best_effort(mut self, best_effort: bool) -> Self {
+    self.options.best_effort = best_effort;
+    self
+  }
+
+  /// Trim item names and collapse each run of whitespace within them into a single space.
+  /// Defaults to `true`.
+  pub fn normalize_names(mut self, normalize_names: bool) -> Self {
+    self.options.normalize_names = normalize_names;
+    self
+  }
+
+  /// Infer the origin of mod rows without an origin span from their link, a Steam Workshop link
+  /// meaning a Steam mod and no link meaning a local mod. Defaults to `true`.
+  pub fn infer_origin(mut self, infer_origin: bool) -> Self {
+    self.options.infer_origin = infer_origin;
+    self
+  }
+
+  /// Reject presets where the same Steam workshop id or DLC id appears more than once,
+  /// see [`Preset::parse_strict`]. Defaults to `false`.
+  pub fn strict_ids(mut self, strict_ids: bool) -> Self {
+    self.options.strict_ids = strict_ids;
+    self
+  }
+
+  /// Accept fragments of a preset, such as a `<div class="mod-list">` pasted on its own, that have
+  /// mod or DLC rows but none of the preset meta tags, parsing them as an Arma 3 [`PresetKind::Preset`][crate::PresetKind::Preset]
+  /// with no name. Documents without meta tags nor any rows still fail with [`Error::NotAPreset`].
+  /// Defaults to `false`.
+  pub fn fragments(mut self, fragments: bool) -> Self {
+    self.options.fragments = fragments;
+    self
+  }
+
+  /// Also accept Steam Workshop and Steam Store links under `hosts`, for presets exported
+  /// behind a mirror or proxy. Links under the real Steam hosts are always accepted.
+  /// Defaults to `None`.
+  pub fn steam_hosts(mut self, hosts: Option<SteamHosts>) -> Self {
+    self.hosts = hosts;
+    self
+  }
+
+  fn options(&self) -> ParseOptions<'_> {
+    ParseOptions { hosts: self.hosts.as_ref(), ..self.options }
+  }
+
+  /// Parses a preset with these options. When lenient, rows that fail to parse are silently skipped.
+  pub fn parse(&self, document_text: &str) -> Result<Preset, Error> {
+    if self.lenient {
+      parse_preset(document_text, self.options(), Some(&mut Vec::new()), None)
+    } else {
+      parse_preset(document_text, self.options(), None, None)
+    }
+  }
+
+  /// Parses a preset with these options, also reporting anything that was recovered from along the way.
+  ///
+  /// When lenient, rows that fail to parse are collected into [`ParseOutcome::errors`].
+  pub fn parse_with_warnings(&self, document_text: &str) -> Result<ParseOutcome, Error> {
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+    let preset = parse_preset(
+      document_text, self.options(),
+      if self.lenient { Some(&mut errors) } else { None },
+      Some(&mut warnings)
+    )?;
+
+    Ok(ParseOutcome { preset, warnings, errors })
+  }
+
+  /// Parses a preset with these options, also counting the rows scanned and timing the parse.
+  ///
+  /// When lenient, rows that fail to parse are skipped and counted in [`ParseStats::skipped_rows`].
+  pub fn parse_with_stats(&self, document_text: &str) -> Result<(Preset, ParseStats), Error> {
+    let start = Instant::now();
+    let mut errors = Vec::new();
+    let preset = parse_preset(document_text, self.options(), if self.lenient { Some(&mut errors) } else { None }, None)?;
+    let elapsed = start.elapsed();
+
+    let skipped_mod_rows = errors.iter().filter(|err| matches!(err, Error::InvalidModRow { .. })).count();
+    let stats = ParseStats {
+      mod_rows: preset.steam_mods.len() + preset.local_mods.len() + skipped_mod_rows,
+      dlc_rows: preset.dlcs.len() + errors.len() - skipped_mod_rows,
+      skipped_rows: errors.len(),
+      document_bytes: document_text.len(),
+      elapsed
+    };
+
+    Ok((preset, stats))
+  }
+
+  /// Parses a preset with these options, always collecting rows that fail to parse rather than
+  /// bailing, as in [`Preset::parse_lenient`].
+  pub fn parse_lenient(&self, document_text: &str) -> (Option<Preset>, Vec<Error>) {
+    let mut errors = Vec::new();
+    match parse_preset(document_text, self.options(), Some(&mut errors), None) {
+      Ok(preset) => (Some(preset), errors),
+      Err(err) => {
+        errors.push(err);
+        (None, errors)
+      }
+    }
+  }
+}
@@ -0,0 +1,455 @@
+use crate::{escape_text, normalize_name, AppId, Error, Preset, PresetDlc, PresetLocalMod, PresetMod, PresetSteamMod, WorkshopId};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Write};
+
+/// A human and machine readable comparison of two named presets, `a` and `b`, see [`Preset::compare`].
+///
+/// Items are categorized as in [`Preset::diff`], with shared items taken from `a`.
+/// The [`Display`][fmt::Display] implementation renders a plain text report listing
+/// each category of Steam mods, DLCs and local mods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ComparisonReport {
+  pub name_a: String,
+  pub name_b: String,
+  pub steam_only_a: Vec<PresetSteamMod>,
+  pub steam_only_b: Vec<PresetSteamMod>,
+  pub steam_shared: Vec<PresetSteamMod>,
+  pub dlc_only_a: Vec<PresetDlc>,
+  pub dlc_only_b: Vec<PresetDlc>,
+  pub dlc_shared: Vec<PresetDlc>,
+  pub local_only_a: Vec<PresetLocalMod>,
+  pub local_only_b: Vec<PresetLocalMod>,
+  pub local_shared: Vec<PresetLocalMod>
+}
+
+impl ComparisonReport {
+  /// Returns true if neither preset has any item the other is missing.
+  pub fn is_identical(&self) -> bool {
+    self.steam_only_a.is_empty() && self.steam_only_b.is_empty() &&
+    self.dlc_only_a.is_empty() && self.dlc_only_b.is_empty() &&
+    self.local_only_a.is_empty() && self.local_only_b.is_empty()
+  }
+
+  /// Renders this report in the style of a unified diff from `a` to `b`, with a `---`/`+++` header
+  /// naming the presets, then a `@@` section for each of Steam mods, DLCs and local mods with changes.
+  ///
+  /// Within each section, the items only in `a` are listed first with a `-` prefix, then the items
+  /// only in `b` with a `+` prefix, each sorted by case-insensitive display name then display name.
+  /// Shared items are not listed. Identical presets render as only the header.
+  pub fn to_unified_diff(&self) -> String {
+    fn write_section<T: fmt::Display>(
+      w: &mut String, header: &str, only_a: &[T], only_b: &[T], display_name: fn(&T) -> &str
+    ) -> fmt::Result {
+      if only_a.is_empty() && only_b.is_empty() {
+        return Ok(());
+      };
+
+      writeln!(w, "@@ {header} @@")?;
+      for (prefix, items) in [('-', only_a), ('+', only_b)] {
+        let mut items = items.iter().collect::<Vec<&T>>();
+        items.sort_by_cached_key(|item| (normalize_name(display_name(item)), display_name(item).to_owned()));
+        for item in items {
+          writeln!(w, "{prefix}{item}")?;
+        };
+      };
+
+      Ok(())
+    }
+
+    let mut out = String::new();
+    writeln!(out, "--- {}", self.name_a).unwrap();
+    writeln!(out, "+++ {}", self.name_b).unwrap();
+    write_section(&mut out, "Steam Mods", &self.steam_only_a, &self.steam_only_b, |m| &m.display_name).unwrap();
+    write_section(&mut out, "DLCs", &self.dlc_only_a, &self.dlc_only_b, |m| &m.display_name).unwrap();
+    write_section(&mut out, "Local Mods", &self.local_only_a, &self.local_only_b, |m| &m.display_name).unwrap();
+    out
+  }
+
+  /// Renders this report as a standalone HTML page, with sections for the items removed
+  /// (only in `a`), added (only in `b`) and shared, in red, green and grey respectively.
+  ///
+  /// Steam mods link to their workshop pages and DLCs to their store pages. Names are escaped.
+  pub fn to_html(&self) -> String {
+    fn write_section(
+      w: &mut String, class: &str, header: fmt::Arguments<'_>,
+      steam_mods: &[PresetSteamMod], dlcs: &[PresetDlc], local_mods: &[PresetLocalMod]
+    ) -> fmt::Result {
+      writeln!(w, "    <section class=\"{class}\">")?;
+      writeln!(w, "      <h2>{header}</h2>")?;
+      writeln!(w, "      <ul>")?;
+      for m in steam_mods {
+        let link = m.steam_workshop_url();
+        writeln!(w, "        <li>Steam: <a href=\"{link}\">{}</a></li>", escape_text(&m.display_name))?;
+      };
+
+      for m in dlcs {
+        let link = m.steam_store_url();
+        writeln!(w, "        <li>DLC: <a href=\"{link}\">{}</a></li>", escape_text(&m.display_name))?;
+      };
+
+      for m in local_mods {
+        writeln!(w, "        <li>Local: {}</li>", escape_text(&m.display_name))?;
+      };
+
+      writeln!(w, "      </ul>")?;
+      writeln!(w, "    </section>")
+    }
+
+    let (name_a, name_b) = (escape_text(&self.name_a), escape_text(&self.name_b));
+    let mut out = String::new();
+    writeln!(out, "<!DOCTYPE html>").unwrap();
+    writeln!(out, "<html>").unwrap();
+    writeln!(out, "  <head>").unwrap();
+    writeln!(out, "    <meta charset=\"utf-8\" />").unwrap();
+    writeln!(out, "    <title>{name_a} compared to {name_b}</title>").unwrap();
+    writeln!(out, "    <style>").unwrap();
+    writeln!(out, "      section {{ margin: 1em 0; padding: 0.5em 1em; border-left: 4px solid; }}").unwrap();
+    writeln!(out, "      .removed {{ border-color: #c0392b; background: #fbeaea; }}").unwrap();
+    writeln!(out, "      .added {{ border-color: #27ae60; background: #eafaf0; }}").unwrap();
+    writeln!(out, "      .shared {{ border-color: #7f8c8d; background: #f4f6f6; }}").unwrap();
+    writeln!(out, "    </style>").unwrap();
+    writeln!(out, "  </head>").unwrap();
+    writeln!(out, "  <body>").unwrap();
+    writeln!(out, "    <h1>{name_a} compared to {name_b}</h1>").unwrap();
+    write_section(
+      &mut out, "removed", format_args!("Only in '{name_a}'"),
+      &self.steam_only_a, &self.dlc_only_a, &self.local_only_a
+    ).unwrap();
+    write_section(
+      &mut out, "added", format_args!("Only in '{name_b}'"),
+      &self.steam_only_b, &self.dlc_only_b, &self.local_only_b
+    ).unwrap();
+    write_section(
+      &mut out, "shared", format_args!("In '{name_a}' and '{name_b}'"),
+      &self.steam_shared, &self.dlc_shared, &self.local_shared
+    ).unwrap();
+    writeln!(out, "  </body>").unwrap();
+    writeln!(out, "</html>").unwrap();
+    out
+  }
+}
+
+impl fmt::Display for ComparisonReport {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fn fmt_list<H, L>(f: &mut fmt::Formatter<'_>, header: H, list: L) -> fmt::Result
+    where H: fmt::Display, L: IntoIterator, L::Item: fmt::Display {
+      let mut printed_header = false;
+      for item in list {
+        if !printed_header {
+          writeln!(f, "{header}")?;
+          printed_header = true;
+        };
+
+        writeln!(f, "- {item}")?;
+      };
+
+      if printed_header {
+        writeln!(f)?;
+      };
+
+      Ok(())
+    }
+
+    let (name_a, name_b) = (&self.name_a, &self.name_b);
+    if self.is_identical() {
+      return writeln!(f, "Presets '{name_a}' and '{name_b}' have identical contents");
+    };
+
+    if self.steam_only_a.is_empty() && self.steam_only_b.is_empty() && self.steam_shared.is_empty() {
+      writeln!(f, "'{name_a}' and '{name_b}' have no Steam Mods\n")?;
+    } else if self.steam_only_a.is_empty() && self.steam_only_b.is_empty() {
+      writeln!(f, "'{name_a}' and '{name_b}' have the same Steam Mods\n")?;
+    } else {
+      fmt_list(f, format_args!("Steam Mods only in '{name_a}'"), &self.steam_only_a)?;
+      fmt_list(f, format_args!("Steam Mods only in '{name_b}'"), &self.steam_only_b)?;
+      fmt_list(f, format_args!("Steam Mods in '{name_a}' and '{name_b}'"), &self.steam_shared)?;
+    };
+
+    if self.dlc_only_a.is_empty() && self.dlc_only_b.is_empty() && self.dlc_shared.is_empty() {
+      writeln!(f, "'{name_a}' and '{name_b}' have no DLCs\n")?;
+    } else if self.dlc_only_a.is_empty() && self.dlc_only_b.is_empty() {
+      writeln!(f, "'{name_a}' and '{name_b}' have the same DLCs\n")?;
+    } else {
+      fmt_list(f, format_args!("DLCs only in '{name_a}'"), &self.dlc_only_a)?;
+      fmt_list(f, format_args!("DLCs only in '{name_b}'"), &self.dlc_only_b)?;
+      fmt_list(f, format_args!("DLCs in '{name_a}' and '{name_b}'"), &self.dlc_shared)?;
+    };
+
+    fmt_list(f, format_args!("Local mods in '{name_a}'"), {
+      self.local_only_a.iter().chain(self.local_shared.iter())
+    })?;
+
+    fmt_list(f, format_args!("Local mods in '{name_b}'"), {
+      self.local_only_b.iter().chain(self.local_shared.iter())
+    })?;
+
+    Ok(())
+  }
+}
+
+/// A comparison of the Steam mods, DLCs and local mods in any number of named presets, see [`Preset::diff_many`].
+///
+/// Each category has its items, their presence in each preset, the items common to every preset,
+/// and for each preset the items unique to it. Steam mods are keyed by id, DLCs by id and local mods
+/// by normalized display name, each item being taken from the first preset containing it.
+///
+/// The [`Display`][fmt::Display] implementation renders a plain text table for each category with any
+/// items, with a row for each item and a column for each preset, marking the presets that contain the item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct MultiDiff {
+  /// The names of the compared presets, in the order they were given.
+  pub names: Vec<String>,
+  /// Every distinct Steam mod in any of the presets, ordered by id.
+  pub steam_mods: Vec<PresetSteamMod>,
+  /// For each Steam mod id, whether each preset (in the order of `names`) contains it.
+  pub steam_presence: BTreeMap<WorkshopId, Vec<bool>>,
+  /// The Steam mods contained in every preset, ordered by id.
+  pub steam_common: Vec<PresetSteamMod>,
+  /// For each preset, the Steam mods contained only in that preset, ordered by id.
+  pub steam_unique: Vec<Vec<PresetSteamMod>>,
+  /// Every distinct DLC in any of the presets, ordered by id.
+  pub dlcs: Vec<PresetDlc>,
+  /// For each DLC id, whether each preset (in the order of `names`) contains it.
+  pub dlc_presence: BTreeMap<AppId, Vec<bool>>,
+  /// The DLCs contained in every preset, ordered by id.
+  pub dlc_common: Vec<PresetDlc>,
+  /// For each preset, the DLCs contained only in that preset, ordered by id.
+  pub dlc_unique: Vec<Vec<PresetDlc>>,
+  /// Every distinct local mod in any of the presets, ordered by normalized display name.
+  pub local_mods: Vec<PresetLocalMod>,
+  /// For each normalized local mod display name, whether each preset (in the order of `names`) contains it.
+  pub local_presence: BTreeMap<String, Vec<bool>>,
+  /// The local mods contained in every preset, ordered by normalized display name.
+  pub local_common: Vec<PresetLocalMod>,
+  /// For each preset, the local mods contained only in that preset, ordered by normalized display name.
+  pub local_unique: Vec<Vec<PresetLocalMod>>
+}
+
+impl fmt::Display for MultiDiff {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fn write_table<'a>(
+      f: &mut fmt::Formatter<'_>, header: &str, names: &[String],
+      rows: impl Iterator<Item = (String, &'a [bool])>
+    ) -> fmt::Result {
+      let rows = rows.collect::<Vec<(String, &[bool])>>();
+      let label_width = rows.iter().map(|(label, _)| label.chars().count())
+        .chain(std::iter::once(header.len())).max().unwrap_or(0);
+
+      write!(f, "{header:label_width$}")?;
+      for name in names.iter() {
+        write!(f, " | {name}")?;
+      };
+
+      writeln!(f)?;
+      for (label, presence) in rows.iter() {
+        write!(f, "{label:label_width$}")?;
+        for (name, &present) in names.iter().zip(presence.iter()) {
+          let width = name.chars().count();
+          write!(f, " | {:width$}", if present { "x" } else { "" })?;
+        };
+
+        writeln!(f)?;
+      };
+
+      Ok(())
+    }
+
+    let mut separator = "";
+    if !self.steam_mods.is_empty() {
+      write_table(f, "Steam Mod", &self.names, self.steam_mods.iter().map(|m| {
+        (format!("{} ({})", m.display_name, m.id), self.steam_presence[&m.id].as_slice())
+      }))?;
+      separator = "\n";
+    };
+
+    if !self.dlcs.is_empty() {
+      f.write_str(separator)?;
+      write_table(f, "DLC", &self.names, self.dlcs.iter().map(|m| {
+        (format!("{} ({})", m.display_name, m.id), self.dlc_presence[&m.id].as_slice())
+      }))?;
+      separator = "\n";
+    };
+
+    if !self.local_mods.is_empty() {
+      f.write_str(separator)?;
+      write_table(f, "Local Mod", &self.names, self.local_mods.iter().map(|m| {
+        (m.display_name.clone(), self.local_presence[&normalize_name(&m.display_name)].as_slice())
+      }))?;
+    };
+
+    Ok(())
+  }
+}
+
+/// The items of one category in a [`MultiDiff`]: every distinct item, their presence,
+/// the items common to every preset, and the items unique to each preset.
+type MultiDiffCategory<K, T> = (Vec<T>, BTreeMap<K, Vec<bool>>, Vec<T>, Vec<Vec<T>>);
+
+fn diff_many_category<'p, K: Ord + Clone, T: Clone + 'p>(
+  presets: &[(&str, &'p Preset)], items: fn(&'p Preset) -> &'p [T], key: fn(&T) -> K
+) -> MultiDiffCategory<K, T> {
+  let mut distinct = BTreeMap::<K, T>::new();
+  let mut presence = BTreeMap::<K, Vec<bool>>::new();
+  for (index, &(_, preset)) in presets.iter().enumerate() {
+    for item in items(preset) {
+      distinct.entry(key(item)).or_insert_with(|| item.clone());
+      presence.entry(key(item)).or_insert_with(|| vec![false; presets.len()])[index] = true;
+    };
+  };
+
+  let common = distinct.iter()
+    .filter(|(key, _)| presence[*key].iter().all(|&present| present))
+    .map(|(_, item)| item.clone()).collect();
+  let unique = (0..presets.len())
+    .map(|index| {
+      distinct.iter()
+        .filter(|(key, _)| {
+          let row = &presence[*key];
+          row[index] && row.iter().filter(|&&present| present).count() == 1
+        })
+        .map(|(_, item)| item.clone()).collect()
+    })
+    .collect();
+
+  (distinct.into_values().collect(), presence, common, unique)
+}
+
+/// The mods changed between two versions of a preset, see [`Preset::changes_since`].
+///
+/// The [`Display`][fmt::Display] implementation renders a plain text changelog,
+/// with a line for each added, removed and renamed mod.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct PresetChanges {
+  /// The mods in the new version that are missing from the previous version.
+  pub added: Vec<PresetMod>,
+  /// The mods in the previous version that are missing from the new version.
+  pub removed: Vec<PresetMod>,
+  /// The Steam mods in both versions whose display name changed.
+  pub renamed: Vec<ModRename>
+}
+
+impl PresetChanges {
+  /// Returns true if no mods were added, removed or renamed.
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty() && self.removed.is_empty() && self.renamed.is_empty()
+  }
+}
+
+impl fmt::Display for PresetChanges {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for m in self.added.iter() {
+      writeln!(f, "+ {}", m.as_mod_ref())?;
+    };
+
+    for m in self.removed.iter() {
+      writeln!(f, "- {}", m.as_mod_ref())?;
+    };
+
+    for rename in self.renamed.iter() {
+      writeln!(f, "~ {rename}")?;
+    };
+
+    Ok(())
+  }
+}
+
+/// A Steam mod whose display name changed between two versions of a preset, see [`PresetChanges::renamed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ModRename {
+  pub id: WorkshopId,
+  pub old_name: String,
+  pub new_name: String
+}
+
+impl fmt::Display for ModRename {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {} -> {}", self.id.workshop_url(), self.old_name, self.new_name)
+  }
+}
+
+impl Preset {
+  /// Lists the mods changed in this preset since `previous`, an earlier version of it.
+  ///
+  /// Mods are matched as in [`Preset::diff`]. A Steam mod present in both versions is listed
+  /// as renamed if its display name differs, taking the name from each version. DLCs are not included.
+  /// Fails with [`Error::GameMismatch`] if the presets are not for the same game.
+  pub fn changes_since(&self, previous: &Preset) -> Result<PresetChanges, Error> {
+    let diff = previous.diff(self)?;
+    let added = diff.steam_only_b.into_iter().cloned().map(PresetMod::Steam)
+      .chain(diff.local_only_b.into_iter().cloned().map(PresetMod::Local))
+      .collect();
+    let removed = diff.steam_only_a.into_iter().cloned().map(PresetMod::Steam)
+      .chain(diff.local_only_a.into_iter().cloned().map(PresetMod::Local))
+      .collect();
+    let mut new_steam_mods = HashMap::<WorkshopId, &PresetSteamMod>::new();
+    for m in self.steam_mods.iter() {
+      new_steam_mods.entry(m.id).or_insert(m);
+    };
+
+    let renamed = diff.steam_shared.into_iter()
+      .filter_map(|old| {
+        let new = new_steam_mods.get(&old.id)?;
+        (new.display_name != old.display_name).then(|| ModRename {
+          id: old.id,
+          old_name: old.display_name.clone(),
+          new_name: new.display_name.clone()
+        })
+      })
+      .collect();
+
+    Ok(PresetChanges { added, removed, renamed })
+  }
+
+  /// Compares the Steam mods, DLCs and local mods of any number of presets, each given with the name to refer to it by.
+  ///
+  /// Steam mods and DLCs are matched by id, and local mods by normalized display name, as in [`Preset::diff`].
+  /// Fails with [`Error::GameMismatch`] if the presets are not all for the same game.
+  pub fn diff_many(presets: &[(&str, &Preset)]) -> Result<MultiDiff, Error> {
+    if let Some(&(_, first)) = presets.first() {
+      for &(_, preset) in presets[1..].iter() {
+        first.assert_same_game(preset)?;
+      };
+    };
+
+    let (steam_mods, steam_presence, steam_common, steam_unique) = diff_many_category(presets, |p| &p.steam_mods, |m| m.id);
+    let (dlcs, dlc_presence, dlc_common, dlc_unique) = diff_many_category(presets, |p| &p.dlcs, |m| m.id);
+    let (local_mods, local_presence, local_common, local_unique) = {
+      diff_many_category(presets, |p| &p.local_mods, |m| normalize_name(&m.display_name))
+    };
+    Ok(MultiDiff {
+      names: presets.iter().map(|&(name, _)| name.to_owned()).collect(),
+      steam_mods, steam_presence, steam_common, steam_unique,
+      dlcs, dlc_presence, dlc_common, dlc_unique,
+      local_mods, local_presence, local_common, local_unique
+    })
+  }
+
+  /// Compares this preset (`a`) against `other` (`b`), producing a [`ComparisonReport`]
+  /// which refers to the presets by `name_a` and `name_b`.
+  ///
+  /// Fails with [`Error::GameMismatch`] if the presets are not for the same game.
+  pub fn compare(&self, other: &Preset, name_a: impl Into<String>, name_b: impl Into<String>) -> Result<ComparisonReport, Error> {
+    let diff = self.diff(other)?;
+    Ok(ComparisonReport {
+      name_a: name_a.into(),
+      name_b: name_b.into(),
+      steam_only_a: diff.steam_only_a.into_iter().cloned().collect(),
+      steam_only_b: diff.steam_only_b.into_iter().cloned().collect(),
+      steam_shared: diff.steam_shared.into_iter().cloned().collect(),
+      dlc_only_a: diff.dlc_only_a.into_iter().cloned().collect(),
+      dlc_only_b: diff.dlc_only_b.into_iter().cloned().collect(),
+      dlc_shared: diff.dlc_shared.into_iter().cloned().collect(),
+      local_only_a: diff.local_only_a.into_iter().cloned().collect(),
+      local_only_b: diff.local_only_b.into_iter().cloned().collect(),
+      local_shared: diff.local_shared.into_iter().cloned().collect()
+    })
+  }
+}
@@ -0,0 +1,41 @@
+//! The selectors used to find mod and DLC rows within a preset document, exposed
+//! so that tools scraping the same documents can stay in sync with the parser.
+//!
+//! These are shared by [`Game::Arma`][crate::Game::Arma] and [`Game::DayZ`][crate::Game::DayZ]
+//! presets, which only differ in the namespace of their meta tags. No DayZ specific row markup
+//! is known of, DayZ exports which deviate from these selectors would need their own.
+
+use scraper::Selector;
+
+lazy_selectors!{
+  pub(crate) static SELECTOR_MOD_CONTAINER = "body > div.mod-list > table tr[data-type=\"ModContainer\"]";
+  pub(crate) static SELECTOR_DLC_CONTAINER = "body > div.dlc-list > table tr[data-type=\"DlcContainer\"]";
+  pub(crate) static SELECTOR_ITEM_NAME = "td[data-type=\"DisplayName\"]";
+  pub(crate) static SELECTOR_ITEM_LINK = "td > a[data-type=\"Link\"]";
+  pub(crate) static SELECTOR_ITEM_ORIGIN = "td > span[class]";
+}
+
+/// Matches each mod row, relative to the document root.
+pub fn mod_container() -> &'static Selector {
+  &SELECTOR_MOD_CONTAINER
+}
+
+/// Matches each DLC row, relative to the document root.
+pub fn dlc_container() -> &'static Selector {
+  &SELECTOR_DLC_CONTAINER
+}
+
+/// Matches the display name cell, relative to a mod or DLC row.
+pub fn item_name() -> &'static Selector {
+  &SELECTOR_ITEM_NAME
+}
+
+/// Matches the Steam link, relative to a mod or DLC row.
+pub fn item_link() -> &'static Selector {
+  &SELECTOR_ITEM_LINK
+}
+
+/// Matches the origin span, whose class is `from-steam` or `from-local`, relative to a mod row.
+pub fn item_origin() -> &'static Selector {
+  &SELECTOR_ITEM_ORIGIN
+}
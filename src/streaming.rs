@@ -0,0 +1,272 @@
+use crate::{parse_document, Error, ParseOptions, Preset, PresetRow, PresetSource};
+
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::states::RawKind;
+use html5ever::tokenizer::{BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult};
+use html5ever::tokenizer::{Tokenizer, TokenizerOpts, TokenizerResult};
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+impl Preset {
+  /// Parses a preset like [`FromStr`][std::str::FromStr], but by streaming through the document's
+  /// tokens instead of building a [`Html`][crate::Html] document, keeping only the meta tags and the
+  /// cells of each row that are needed. This uses considerably less memory for very large presets.
+  ///
+  /// Rows are matched by following the same structure as the [`selectors`][crate::selectors], and
+  /// produce the same preset as [`FromStr`][std::str::FromStr] for any document exported by the launcher.
+  /// Error messages for malformed rows describe the row rather than quoting its HTML.
+  /// As no tree is built, deeply nested documents are not rejected, see [`MAX_NESTING_DEPTH`][crate::MAX_NESTING_DEPTH].
+  pub fn parse_streaming(document_text: &str) -> Result<Preset, Error> {
+    let document = StreamedDocument::tokenize(document_text.strip_prefix('\u{feff}').unwrap_or(document_text));
+    parse_document(&document, Some(document_text), ParseOptions::default(), None, None).map(Preset::from)
+  }
+}
+
+#[derive(Debug, Default)]
+struct StreamedDocument {
+  meta_tags: Vec<(String, String)>,
+  header_comments: Vec<String>,
+  mod_rows: Vec<StreamedRow>,
+  dlc_rows: Vec<StreamedRow>
+}
+
+#[derive(Debug, Default)]
+struct StreamedRow {
+  name: Option<String>,
+  origin: Option<String>,
+  /// The first link in the row, along with its `href` if it has one.
+  link: Option<Option<String>>
+}
+
+impl StreamedDocument {
+  fn tokenize(document_text: &str) -> Self {
+    let tokenizer = Tokenizer::new(StreamSink::default(), TokenizerOpts::default());
+    let queue = BufferQueue::default();
+    queue.push_back(StrTendril::from_slice(document_text));
+    while let TokenizerResult::Script(()) = tokenizer.feed(&queue) {};
+    tokenizer.end();
+    tokenizer.sink.state.into_inner().document
+  }
+}
+
+impl<'a> PresetSource<'a> for &'a StreamedDocument {
+  type Row = &'a StreamedRow;
+
+  fn meta_tags(&self) -> impl Iterator<Item = (&'a str, &'a str)> {
+    self.meta_tags.iter().map(|(name, content)| (name.as_str(), content.as_str()))
+  }
+
+  fn head_snippet(&self) -> String {
+    "<head> without a preset type meta tag".to_owned()
+  }
+
+  fn mod_rows(&self) -> impl Iterator<Item = Self::Row> {
+    self.mod_rows.iter()
+  }
+
+  fn dlc_rows(&self) -> impl Iterator<Item = Self::Row> {
+    self.dlc_rows.iter()
+  }
+
+  fn header_comments(&self) -> impl Iterator<Item = &'a str> {
+    self.header_comments.iter().map(String::as_str)
+  }
+}
+
+impl<'a> PresetRow<'a> for &'a StreamedRow {
+  fn name(self) -> Result<Cow<'a, str>, Error> {
+    self.name.as_deref().map(Cow::Borrowed)
+      .ok_or_else(|| Error::SelectorFailedItemName("<tr> without a display name cell".to_owned(), None))
+  }
+
+  fn origin(self) -> Result<&'a str, Error> {
+    self.origin.as_deref()
+      .ok_or_else(|| Error::SelectorFailedItemOrigin("<tr> without an origin span".to_owned(), None))
+  }
+
+  fn link(self) -> Result<&'a str, Error> {
+    self.link.as_ref().and_then(Option::as_deref)
+      .ok_or_else(|| Error::SelectorFailedItemLink("<tr> without a link".to_owned(), None))
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowKind {
+  Mod,
+  Dlc
+}
+
+#[derive(Debug)]
+struct OpenElement {
+  name: String,
+  /// Set on `div.mod-list` and `div.dlc-list` elements.
+  list: Option<RowKind>
+}
+
+#[derive(Debug, Default)]
+struct StreamState {
+  document: StreamedDocument,
+  open_elements: Vec<OpenElement>,
+  in_body: bool,
+  /// The row currently being read, with the depth of its `<tr>` in `open_elements`.
+  row: Option<(RowKind, usize, StreamedRow)>,
+  /// The text of the display name cell currently being read, with the depth of its `<td>`.
+  name: Option<(usize, String)>
+}
+
+#[derive(Debug, Default)]
+struct StreamSink {
+  state: RefCell<StreamState>
+}
+
+impl TokenSink for StreamSink {
+  type Handle = ();
+
+  fn process_token(&self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+    let mut state = self.state.borrow_mut();
+    match token {
+      Token::TagToken(tag) if tag.kind == TagKind::StartTag => return state.start_tag(tag),
+      Token::TagToken(tag) => state.end_tag(&tag.name),
+      Token::CommentToken(text) if !state.in_body => state.document.header_comments.push(text.to_string()),
+      Token::CharacterTokens(text) => {
+        if let Some((_, name)) = state.name.as_mut() {
+          name.push_str(&text);
+        };
+      },
+      _ => ()
+    };
+
+    TokenSinkResult::Continue
+  }
+
+  fn end(&self) {
+    self.state.borrow_mut().close_to(0);
+  }
+}
+
+const VOID_ELEMENTS: &[&str] = &[
+  "area", "base", "br", "col", "embed", "hr", "img", "input",
+  "link", "meta", "param", "source", "track", "wbr"
+];
+
+fn attr<'t>(tag: &'t Tag, name: &str) -> Option<&'t str> {
+  tag.attrs.iter().find(|attr| &*attr.name.local == name).map(|attr| &*attr.value)
+}
+
+impl StreamState {
+  fn start_tag(&mut self, tag: Tag) -> TokenSinkResult<()> {
+    let name = &*tag.name;
+    match name {
+      "meta" if !self.in_body => {
+        if let (Some(meta_name), Some(content)) = (attr(&tag, "name"), attr(&tag, "content")) {
+          self.document.meta_tags.push((meta_name.to_owned(), content.to_owned()));
+        };
+      },
+      "head" | "html" | "meta" | "title" | "style" | "script" | "link" | "base" => (),
+      _ => self.in_body = true
+    };
+
+    // Cells and rows are implicitly closed by the next cell or row
+    if matches!(name, "td" | "th" | "tr") {
+      let boundary = if name == "tr" { &["table"][..] } else { &["tr", "table"][..] };
+      let open = self.open_elements.iter().rposition(|element| {
+        boundary.contains(&element.name.as_str()) || matches!(element.name.as_str(), "td" | "th" | "tr")
+      });
+      if let Some(depth) = open.filter(|&depth| !boundary.contains(&self.open_elements[depth].name.as_str())) {
+        self.close_to(depth);
+      };
+    };
+
+    let depth = self.open_elements.len();
+    let in_row_cell = self.row.is_some() && self.open_elements.iter().any(|element| element.name == "td");
+    match name {
+      "tr" => {
+        let data_type = attr(&tag, "data-type");
+        let list = self.enclosing_list();
+        let in_table = self.open_elements.iter().any(|element| element.name == "table");
+        let kind = match (data_type, list) {
+          (Some("ModContainer"), Some(RowKind::Mod)) if in_table => Some(RowKind::Mod),
+          (Some("DlcContainer"), Some(RowKind::Dlc)) if in_table => Some(RowKind::Dlc),
+          _ => None
+        };
+
+        if let Some(kind) = kind.filter(|_| self.row.is_none()) {
+          self.row = Some((kind, depth, StreamedRow::default()));
+        };
+      },
+      "td" if attr(&tag, "data-type") == Some("DisplayName") && self.name.is_none() &&
+        self.row.as_ref().is_some_and(|(_, _, row)| row.name.is_none()) => {
+        self.name = Some((depth, String::new()));
+      },
+      "span" if in_row_cell => {
+        if let (Some((_, _, row)), Some(class)) = (self.row.as_mut(), attr(&tag, "class")) {
+          row.origin.get_or_insert_with(|| class.to_owned());
+        };
+      },
+      "a" if in_row_cell && attr(&tag, "data-type") == Some("Link") => {
+        if let Some((_, _, row)) = self.row.as_mut() {
+          row.link.get_or_insert_with(|| attr(&tag, "href").map(str::to_owned));
+        };
+      },
+      _ => ()
+    };
+
+    if tag.self_closing || VOID_ELEMENTS.contains(&name) {
+      return TokenSinkResult::Continue;
+    };
+
+    let list = match name {
+      "div" => attr(&tag, "class").and_then(|class| {
+        class.split_ascii_whitespace().find_map(|class| match class {
+          "mod-list" => Some(RowKind::Mod),
+          "dlc-list" => Some(RowKind::Dlc),
+          _ => None
+        })
+      }),
+      _ => None
+    };
+
+    self.open_elements.push(OpenElement { name: name.to_owned(), list });
+    match name {
+      "title" | "textarea" => TokenSinkResult::RawData(RawKind::Rcdata),
+      "style" | "xmp" | "iframe" | "noembed" | "noframes" => TokenSinkResult::RawData(RawKind::Rawtext),
+      "script" => TokenSinkResult::RawData(RawKind::ScriptData),
+      "plaintext" => TokenSinkResult::Plaintext,
+      _ => TokenSinkResult::Continue
+    }
+  }
+
+  fn end_tag(&mut self, name: &str) {
+    if name == "head" {
+      self.in_body = true;
+    };
+
+    if let Some(depth) = self.open_elements.iter().rposition(|element| element.name == name) {
+      self.close_to(depth);
+    };
+  }
+
+  /// Closes the open element at `depth` and every element within it, finishing any name cell or row they held.
+  fn close_to(&mut self, depth: usize) {
+    self.open_elements.truncate(depth);
+    if self.name.as_ref().is_some_and(|&(name_depth, _)| name_depth >= depth) {
+      let (_, name) = self.name.take().unwrap();
+      if let Some((_, _, row)) = self.row.as_mut() {
+        row.name = Some(name);
+      };
+    };
+
+    if self.row.as_ref().is_some_and(|&(_, row_depth, _)| row_depth >= depth) {
+      let (kind, _, row) = self.row.take().unwrap();
+      match kind {
+        RowKind::Mod => self.document.mod_rows.push(row),
+        RowKind::Dlc => self.document.dlc_rows.push(row)
+      };
+    };
+  }
+
+  fn enclosing_list(&self) -> Option<RowKind> {
+    self.open_elements.iter().rev().find_map(|element| element.list)
+  }
+}
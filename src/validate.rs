@@ -0,0 +1,81 @@
+use crate::{normalize_name, AppId, Preset, WorkshopId};
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// A structural problem with a preset, see [`Preset::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+  /// A Steam mod id appears more than once.
+  DuplicateSteamId(WorkshopId),
+  /// A DLC id appears more than once.
+  DuplicateDlcId(AppId),
+  /// A Steam mod has a blank display name.
+  BlankSteamModName(WorkshopId),
+  /// The local mod at this index has a blank display name.
+  BlankLocalModName(usize),
+  /// Two local mods have display names differing only in case or surrounding whitespace.
+  LocalModNameCollision(String, String)
+}
+
+impl fmt::Display for ValidationIssue {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ValidationIssue::DuplicateSteamId(id) => write!(f, "steam mod {id} appears more than once"),
+      ValidationIssue::DuplicateDlcId(id) => write!(f, "dlc {id} appears more than once"),
+      ValidationIssue::BlankSteamModName(id) => write!(f, "steam mod {id} has a blank name"),
+      ValidationIssue::BlankLocalModName(index) => write!(f, "local mod {index} has a blank name"),
+      ValidationIssue::LocalModNameCollision(a, b) => write!(f, "local mods {a:?} and {b:?} differ only in case or surrounding whitespace")
+    }
+  }
+}
+
+impl Preset {
+  /// Checks this preset for structural problems, such as duplicate ids or blank names.
+  ///
+  /// A preset that parsed successfully may still have issues, an empty list means none were found.
+  /// Each duplicate id is only reported once, no matter how many times it reappears.
+  pub fn validate(&self) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    let mut seen_steam_ids = HashSet::new();
+    let mut duplicate_steam_ids = HashSet::new();
+    for m in self.steam_mods.iter() {
+      if !seen_steam_ids.insert(m.id) && duplicate_steam_ids.insert(m.id) {
+        issues.push(ValidationIssue::DuplicateSteamId(m.id));
+      };
+
+      if m.display_name.trim().is_empty() {
+        issues.push(ValidationIssue::BlankSteamModName(m.id));
+      };
+    };
+
+    let mut seen_dlc_ids = HashSet::new();
+    let mut duplicate_dlc_ids = HashSet::new();
+    for m in self.dlcs.iter() {
+      if !seen_dlc_ids.insert(m.id) && duplicate_dlc_ids.insert(m.id) {
+        issues.push(ValidationIssue::DuplicateDlcId(m.id));
+      };
+    };
+
+    let mut seen_local_names = HashMap::<String, &str>::new();
+    for (index, m) in self.local_mods.iter().enumerate() {
+      if m.display_name.trim().is_empty() {
+        issues.push(ValidationIssue::BlankLocalModName(index));
+        continue;
+      };
+
+      match seen_local_names.get(&normalize_name(&m.display_name)) {
+        Some(&first) if first != m.display_name.as_str() => {
+          issues.push(ValidationIssue::LocalModNameCollision(first.to_owned(), m.display_name.clone()));
+        },
+        Some(..) => (),
+        None => {
+          seen_local_names.insert(normalize_name(&m.display_name), m.display_name.as_str());
+        }
+      };
+    };
+
+    issues
+  }
+}
@@ -0,0 +1,129 @@
+use crate::{element_snippet, get_steam_link_steam_workshop_id, parse_html, select_text};
+use crate::{Error, Game, Preset, PresetKind, PresetSteamMod, WorkshopId};
+#[cfg(feature = "reqwest")]
+use crate::SteamHosts;
+
+use scraper::ElementRef;
+
+/// An error encountered while fetching data from Steam, see [`Preset::resolve_names`].
+#[cfg(feature = "reqwest")]
+#[derive(Debug, thiserror::Error)]
+pub enum FetchError {
+  #[error("request to steam failed: {0}")]
+  Request(#[from] reqwest::Error)
+}
+
+lazy_selectors!{
+  static SELECTOR_TITLE = "head > title";
+  static SELECTOR_COLLECTION_ITEM = "div.collectionItem";
+  static SELECTOR_COLLECTION_ITEM_TITLE = "div.collectionItemDetails .workshopItemTitle";
+  static SELECTOR_COLLECTION_ITEM_LINK = "div.collectionItemDetails a[href]";
+}
+
+const TITLE_PREFIX: &str = "Steam Workshop::";
+
+impl Preset {
+  /// Parses a Steam Workshop collection page into a preset containing only Steam mods.
+  ///
+  /// Collection pages do not say which game they are for, so it is supplied by `game`,
+  /// and the resulting preset is always of kind [`PresetKind::List`].
+  ///
+  /// Each mod is read from a `div.collectionItem` element, taking its id from the
+  /// `id="sharedfile_<id>"` attribute, or failing that, from the workshop link of an
+  /// `a[href]` within its `div.collectionItemDetails`. Its name is taken from the
+  /// `.workshopItemTitle` within `div.collectionItemDetails`. The preset name is
+  /// taken from the page `<title>`, with its `Steam Workshop::` prefix removed.
+  pub fn from_workshop_collection_html(document_text: &str, game: Game) -> Result<Preset, Error> {
+    let document = parse_html(document_text)?;
+
+    let preset_name = document.select(&SELECTOR_TITLE).next()
+      .map(|element| select_text(element))
+      .map(|title| title.trim().strip_prefix(TITLE_PREFIX).unwrap_or(title.trim()).to_owned())
+      .filter(|title| !title.is_empty());
+
+    let steam_mods = document.select(&SELECTOR_COLLECTION_ITEM)
+      .map(parse_collection_item)
+      .collect::<Result<Vec<PresetSteamMod>, Error>>()?;
+
+    Ok(Preset {
+      game,
+      kind: PresetKind::List,
+      preset_name,
+      steam_mods,
+      local_mods: Vec::new(),
+      dlcs: Vec::new(),
+      extra_meta: Vec::new(),
+      generated_by: None,
+      generated_at: None
+    })
+  }
+}
+
+#[cfg(feature = "reqwest")]
+impl Preset {
+  /// Fetches the Steam Workshop page of each Steam mod with a missing display name,
+  /// and fills in its name from the page's `div.workshopItemTitle`.
+  ///
+  /// A name is considered missing if it is blank or is just the mod's id. Mods whose page
+  /// does not exist or has no title are left as they are. Requests are made one at a time,
+  /// and require a Tokio runtime, as with any use of [`reqwest`].
+  pub async fn resolve_names(&mut self) -> Result<(), FetchError> {
+    self.resolve_names_on(&SteamHosts::default()).await
+  }
+
+  /// Like [`Preset::resolve_names`], but fetching workshop pages from the workshop host of `hosts`,
+  /// as for a mirror or proxy.
+  pub async fn resolve_names_on(&mut self, hosts: &SteamHosts) -> Result<(), FetchError> {
+    let client = reqwest::Client::new();
+    for m in self.steam_mods.iter_mut() {
+      let display_name = m.display_name.trim();
+      if !display_name.is_empty() && display_name != m.id.to_string() {
+        continue;
+      };
+
+      let response = client.get(m.steam_workshop_url_on(hosts)).send().await?;
+      if response.status() == reqwest::StatusCode::NOT_FOUND {
+        continue;
+      };
+
+      let document_text = response.error_for_status()?.text().await?;
+      if let Some(title) = select_workshop_item_title(&document_text) {
+        m.display_name = title;
+      };
+    };
+
+    Ok(())
+  }
+}
+
+#[cfg(feature = "reqwest")]
+fn select_workshop_item_title(document_text: &str) -> Option<String> {
+  static SELECTOR_ITEM_TITLE: std::sync::LazyLock<scraper::Selector> = lazy_selector!("div.workshopItemTitle");
+
+  let document = parse_html(document_text).ok()?;
+  document.select(&SELECTOR_ITEM_TITLE).next()
+    .map(|element| select_text(element).trim().to_owned())
+    .filter(|title| !title.is_empty())
+}
+
+fn parse_collection_item(element: ElementRef<'_>) -> Result<PresetSteamMod, Error> {
+  let display_name = element.select(&SELECTOR_COLLECTION_ITEM_TITLE).next()
+    .map(|element| select_text(element).trim().to_owned())
+    .ok_or_else(|| Error::SelectorFailedItemName(element_snippet(element), None))?;
+
+  let id = element.value().id()
+    .and_then(|id| id.strip_prefix("sharedfile_"))
+    .and_then(|id| id.parse::<WorkshopId>().ok());
+  let id = match id {
+    Some(id) => id,
+    None => {
+      let link = element.select(&SELECTOR_COLLECTION_ITEM_LINK).next()
+        .and_then(|element| element.value().attr("href"))
+        .ok_or_else(|| Error::SelectorFailedItemLink(element_snippet(element), None))?;
+      get_steam_link_steam_workshop_id(link, None)
+        .ok_or_else(|| Error::InvalidItemLinkSteamWorkshop(link.to_owned(), None))?
+    }
+  };
+
+  Ok(PresetSteamMod { display_name, id, order: None })
+}
@@ -0,0 +1,24 @@
+use a3_preset_parser::{AppId, Game, MergeBuilder, PresetBuilder, WorkshopId};
+
+#[test]
+fn merge_provenance() {
+  let a = PresetBuilder::new(Game::Arma).steam_mod(1u64, "A").steam_mod(2u64, "B").dlc(1u64, "DLC").build();
+  let b = PresetBuilder::new(Game::Arma).steam_mod(2u64, "B").steam_mod(3u64, "C").steam_mod(3u64, "C").build();
+  let (preset, provenance) = MergeBuilder::new(Game::Arma).entry("a", a).entry("b", b).build().unwrap();
+
+  let ids = preset.steam_mods.iter().map(|m| m.id.0).collect::<Vec<u64>>();
+  assert_eq!(ids, [1, 2, 3]);
+  assert_eq!(provenance.steam_mods[&WorkshopId(1)], ["a"]);
+  assert_eq!(provenance.steam_mods[&WorkshopId(2)], ["a", "b"]);
+  assert_eq!(provenance.steam_mods[&WorkshopId(3)], ["b"]);
+  assert_eq!(provenance.steam_mods.len(), 3);
+  // A DLC sharing a number with a Steam mod is tracked separately.
+  assert_eq!(provenance.dlcs[&AppId(1)], ["a"]);
+  assert_eq!(provenance.dlcs.len(), 1);
+}
+
+#[test]
+fn merge_rejects_other_games() {
+  let dayz = PresetBuilder::new(Game::DayZ).steam_mod(1559212036u64, "Community Framework").build();
+  assert!(MergeBuilder::new(Game::Arma).entry("dayz", dayz).build().is_err());
+}
@@ -0,0 +1,103 @@
+use a3_preset_parser::{Error, ErrorKind, Game, OutputFormat, Preset, PresetBuilder, SourceLocation};
+
+const ARMA_PRESET: &str = include_str!("fixtures/arma_preset.html");
+const DAYZ_LIST: &str = include_str!("fixtures/dayz_list.html");
+
+fn parse_err(document_text: &str) -> Error {
+  document_text.parse::<Preset>().unwrap_err()
+}
+
+#[test]
+fn document_errors() {
+  let err = parse_err("<html><body><p>hello</p></body></html>");
+  assert!(matches!(err, Error::NotAPreset));
+  assert_eq!(err.kind(), ErrorKind::NotAPreset);
+  assert!(!err.is_recoverable());
+
+  let err = parse_err(&format!("<html><body>{}</body></html>", "<div>".repeat(1000)));
+  assert!(matches!(err, Error::NestingTooDeep));
+  assert_eq!(err.kind(), ErrorKind::NestingTooDeep);
+  assert!(!err.is_recoverable());
+
+  let err = parse_err(&DAYZ_LIST.replace("content=\"list\"", "content=\"playlist\""));
+  assert!(matches!(err, Error::InvalidPresetTypeValue(..)));
+  assert_eq!(err.kind(), ErrorKind::UnknownValue);
+  assert!(!err.is_recoverable());
+}
+
+#[test]
+fn row_errors() {
+  let err = parse_err(&ARMA_PRESET.replacen("class=\"from-local\"", "class=\"from-mars\"", 1));
+  assert_eq!(err.kind(), ErrorKind::UnknownValue);
+  assert!(err.is_recoverable());
+
+  let err = parse_err(&ARMA_PRESET.replacen("<td data-type=\"DisplayName\">ace</td>", "<td>ace</td>", 1));
+  assert_eq!(err.kind(), ErrorKind::MalformedItem);
+  assert!(err.is_recoverable());
+
+  let link = "href=\"https://steamcommunity.com/sharedfiles/filedetails/?id=463939057\"";
+  let err = parse_err(&ARMA_PRESET.replacen(link, "href=\"https://example.com/\"", 1));
+  assert_eq!(err.kind(), ErrorKind::BadLink);
+  assert!(err.is_recoverable());
+
+  let link = "href=\"https://store.steampowered.com/app/1021790\"";
+  let err = parse_err(&ARMA_PRESET.replacen(link, "href=\"https://store.steampowered.com/\"", 1));
+  assert_eq!(err.kind(), ErrorKind::BadLink);
+  assert!(err.is_recoverable());
+}
+
+#[test]
+fn lenient_row_errors_keep_their_kind() {
+  let document_text = ARMA_PRESET.replacen("class=\"from-local\"", "class=\"from-mars\"", 1);
+  let (preset, errors) = Preset::parse_lenient(&document_text);
+  assert_eq!(preset.unwrap().local_mods.len(), 1);
+  assert!(matches!(&errors[..], [Error::InvalidModRow { index: 1, .. }]));
+  assert_eq!(errors[0].kind(), ErrorKind::UnknownValue);
+  assert!(errors[0].is_recoverable());
+}
+
+#[test]
+fn duplicate_ids() {
+  let err = Preset::parse_strict(&ARMA_PRESET.replace("463939057", "450814997")).unwrap_err();
+  assert!(matches!(err, Error::DuplicateSteamId(id) if id == 450814997u64));
+  assert_eq!(err.kind(), ErrorKind::DuplicateId);
+  assert!(!err.is_recoverable());
+}
+
+#[test]
+fn other_errors() {
+  let err = PresetBuilder::new(Game::Arma).build().diff(&PresetBuilder::new(Game::DayZ).build()).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::GameMismatch);
+  assert!(!err.is_recoverable());
+
+  let err = Preset::from_file("tests/fixtures/missing.html").unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::Io);
+  assert!(!err.is_recoverable());
+
+  let err = "Arma 2".parse::<Game>().unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::UnknownValue);
+  let err = "yaml".parse::<OutputFormat>().unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::UnknownValue);
+}
+
+#[test]
+fn row_error_locations() {
+  let document_text = ARMA_PRESET
+    .replacen("class=\"from-local\"", "class=\"from-mars\"", 1)
+    .replacen("href=\"https://store.steampowered.com/app/1021790\"", "href=\"https://store.steampowered.com/\"", 1);
+  let mod_row = document_text.match_indices("<tr data-type=\"ModContainer\">").nth(1).unwrap().0;
+  let dlc_row = document_text.find("<tr data-type=\"DlcContainer\">").unwrap();
+
+  let err = parse_err(&document_text);
+  assert_eq!(err.location(), Some(SourceLocation { offset: mod_row, line: 35, column: 9 }));
+  assert!(err.to_string().contains(" at line 35, column 9"));
+
+  let (_, errors) = Preset::parse_lenient(&document_text);
+  let locations = errors.iter().map(Error::location).collect::<Vec<_>>();
+  assert_eq!(locations, [
+    Some(SourceLocation { offset: mod_row, line: 35, column: 9 }),
+    Some(SourceLocation { offset: dlc_row, line: 62, column: 9 })
+  ]);
+
+  assert_eq!(Error::NotAPreset.location(), None);
+}
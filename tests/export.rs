@@ -0,0 +1,244 @@
+use a3_preset_parser::{DisplayLabels, Game, ItemId, ModNameMap, ModRef, OutputFormat, Preset, PresetBuilder};
+
+fn mixed_preset() -> Preset {
+  PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA_A3")
+    .local_mod("My Server/Mod")
+    .build()
+}
+
+#[test]
+fn folder_names_without_safe_characters() {
+  let preset = PresetBuilder::new(Game::Arma).local_mod("日本").local_mod("한국").local_mod("  ").local_mod("@ok mod").build();
+  let names = ModNameMap::new();
+  let folders = preset.all_mods().map(|m| names.folder_name(m)).collect::<Vec<String>>();
+  assert_eq!(folders, ["@local_e697a5e69cac", "@local_ed959ceab5ad", "@local_", "@ok_mod"]);
+}
+
+#[test]
+fn server_modlist_cfg() {
+  let expected = "class ModList\n{\n\tmods[] =\n\t{\n\t\t\"@450814997\",\n\t\t\"@My_ServerMod\"\n\t};\n};\n";
+  assert_eq!(mixed_preset().to_server_modlist_cfg(), expected);
+
+  let names = ModNameMap::from_pairs([("My Server/Mod", "@server")]);
+  assert!(mixed_preset().to_server_modlist_cfg_mapped(&names).contains("\t\t\"@server\"\n"));
+
+  let quoted = mixed_preset().to_server_modlist_cfg_with(|m| match m {
+    ModRef::Steam(m) => m.id.to_string(),
+    ModRef::Local(..) => "say \"hi\"".to_owned()
+  });
+  assert!(quoted.contains("\t\t\"say \"\"hi\"\"\"\n"));
+}
+
+#[test]
+fn mod_param() {
+  let preset = PresetBuilder::new(Game::Arma).steam_mod(450814997u64, "CBA_A3").local_mod("Tweaks; Extra").build();
+  assert_eq!(preset.to_mod_param(None), "-mod=@450814997;@Tweaks_Extra");
+  assert_eq!(preset.to_mod_param(Some("C:\\Arma 3\\")), "-mod=C:\\Arma 3\\@450814997;C:\\Arma 3\\@Tweaks_Extra");
+  assert_eq!(preset.to_mod_param(Some("/srv/arma3/")), "-mod=/srv/arma3/@450814997;/srv/arma3/@Tweaks_Extra");
+  assert_eq!(preset.to_mod_param(Some("C:/Arma 3")), "-mod=C:/Arma 3/@450814997;C:/Arma 3/@Tweaks_Extra");
+}
+
+#[test]
+fn mod_param_single_origin() {
+  let steam_only = PresetBuilder::new(Game::Arma).steam_mod(450814997u64, "CBA_A3").steam_mod(463939057u64, "ace").build();
+  assert_eq!(steam_only.to_mod_param(None), "-mod=@450814997;@463939057");
+  assert_eq!(steam_only.to_mod_param(Some("mods")), "-mod=mods/@450814997;mods/@463939057");
+
+  let local_only = PresetBuilder::new(Game::Arma).local_mod("Server Keys").local_mod("@Tweaks").build();
+  assert_eq!(local_only.to_mod_param(None), "-mod=@Server_Keys;@Tweaks");
+  assert_eq!(local_only.to_mod_param(Some("D:\\Mods")), "-mod=D:\\Mods\\@Server_Keys;D:\\Mods\\@Tweaks");
+}
+
+#[test]
+fn arma3sync_modset() {
+  let preset = PresetBuilder::new(Game::Arma).steam_mod(450814997u64, "CBA_A3").local_mod("Tweaks").build();
+  assert_eq!(preset.to_arma3sync_modset("Ops"), "[Ops]\n@450814997\n@Tweaks\n");
+  assert_eq!(preset.to_arma3sync_modset("Ops]\n[Other"), "[Ops__[Other]\n@450814997\n@Tweaks\n");
+}
+
+#[test]
+fn markdown() {
+  let preset = PresetBuilder::new(Game::Arma)
+    .name("Ops | Night")
+    .steam_mod(450814997u64, "CBA_A3")
+    .local_mod("Tweaks|`v2`")
+    .dlc(1021790u64, "Contact")
+    .build();
+  let expected = concat!(
+    "# Ops \\| Night\n\n",
+    "| Name | Source | Link |\n",
+    "| --- | --- | --- |\n",
+    "| CBA\\_A3 | Steam | [https://steamcommunity.com/sharedfiles/filedetails/?id=450814997](https://steamcommunity.com/sharedfiles/filedetails/?id=450814997) |\n",
+    "| Tweaks\\|\\`v2\\` | Local | |\n",
+    "| Contact | DLC | [https://store.steampowered.com/app/1021790](https://store.steampowered.com/app/1021790) |\n"
+  );
+  assert_eq!(preset.to_markdown(), expected);
+}
+
+#[test]
+fn task_list() {
+  let preset = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA_A3")
+    .local_mod("Tweaks")
+    .dlc(1021790u64, "Contact")
+    .build();
+  let expected = "- [ ] CBA\\_A3 (https://steamcommunity.com/sharedfiles/filedetails/?id=450814997)\n- [ ] Tweaks\n";
+  assert_eq!(preset.to_task_list(), expected);
+}
+
+#[test]
+fn rows_in_order() {
+  let preset = PresetBuilder::new(Game::Arma)
+    .local_mod("Tweaks")
+    .dlc(1021790u64, "Contact")
+    .steam_mod(450814997u64, "CBA_A3")
+    .build();
+  let rows = preset.rows().map(|(origin, name, id)| (origin.to_string(), name, id)).collect::<Vec<_>>();
+  assert_eq!(rows, [
+    ("steam".to_owned(), "CBA_A3", Some(ItemId::Workshop(450814997u64.into()))),
+    ("local".to_owned(), "Tweaks", None),
+    ("dlc".to_owned(), "Contact", Some(ItemId::App(1021790u64.into())))
+  ]);
+}
+
+#[test]
+fn csv_quotes_fields() {
+  let preset = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA, \"Community Base Addons\"")
+    .local_mod("Tweaks")
+    .dlc(1021790u64, "Contact")
+    .build();
+  let expected = concat!(
+    "kind,id,display_name\n",
+    "steam,450814997,\"CBA, \"\"Community Base Addons\"\"\"\n",
+    "local,,Tweaks\n",
+    "dlc,1021790,Contact\n"
+  );
+  assert_eq!(preset.to_csv(), expected);
+}
+
+#[test]
+fn markdown_escapes_table_syntax() {
+  let preset = PresetBuilder::new(Game::DayZ).local_mod("a|b `c` [d]").build();
+  let markdown = preset.to_markdown();
+  assert!(markdown.starts_with("# DayZ Preset\n\n"), "{markdown}");
+  assert!(markdown.contains("| a\\|b \\`c\\` \\[d\\] | Local | |\n"), "{markdown}");
+}
+
+#[test]
+fn unified_diff() {
+  let a = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA_A3")
+    .steam_mod(463939057u64, "ace")
+    .local_mod("Tweaks")
+    .build();
+  let b = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA_A3")
+    .steam_mod(333310405u64, "Enhanced Movement")
+    .dlc(1021790u64, "Contact")
+    .local_mod("Tweaks")
+    .build();
+
+  let expected = "\
+--- old
++++ new
+@@ Steam Mods @@
+-https://steamcommunity.com/sharedfiles/filedetails/?id=463939057: ace
++https://steamcommunity.com/sharedfiles/filedetails/?id=333310405: Enhanced Movement
+@@ DLCs @@
++https://store.steampowered.com/app/1021790: Contact
+";
+  assert_eq!(a.compare(&b, "old", "new").unwrap().to_unified_diff(), expected);
+  assert_eq!(a.compare(&a, "old", "new").unwrap().to_unified_diff(), "--- old\n+++ new\n");
+}
+
+#[test]
+fn display_with_labels() {
+  let preset = PresetBuilder::new(Game::Arma)
+    .name("Mon Preset")
+    .steam_mod(450814997u64, "CBA_A3")
+    .local_mod("Tweaks")
+    .dlc(1021790u64, "Contact")
+    .build();
+  assert_eq!(preset.display_with_labels(&DisplayLabels::default()).to_string(), preset.to_string());
+
+  let labels = DisplayLabels { header: Some("Préréglage Arma 3"), steam: "Steam", local: "Locale", dlc: "DLC" };
+  let expected = "\
+Préréglage Arma 3: Mon Preset
+Steam: https://steamcommunity.com/sharedfiles/filedetails/?id=450814997: CBA_A3
+Locale: Tweaks
+DLC: https://store.steampowered.com/app/1021790: Contact
+";
+  assert_eq!(preset.display_with_labels(&labels).to_string(), expected);
+}
+
+#[test]
+fn safe_file_stem() {
+  let stem = |name: Option<&str>| {
+    let mut preset = PresetBuilder::new(Game::Arma).build();
+    preset.preset_name = name.map(str::to_owned);
+    preset.safe_file_stem()
+  };
+
+  assert_eq!(stem(Some("Ops: Training")), "Ops_ Training");
+  assert_eq!(stem(Some("Ops/Training\\Old")), "Ops_Training_Old");
+  assert_eq!(stem(Some("Ops  \t Training")), "Ops Training");
+  assert_eq!(stem(Some("Ops Training...")), "Ops Training");
+  assert_eq!(stem(Some("Ops Training. . ")), "Ops Training");
+  assert_eq!(stem(Some("CON")), "CON_");
+  assert_eq!(stem(Some("lpt1.old")), "lpt1_.old");
+  assert_eq!(stem(Some("...")), "Arma 3 Preset");
+  assert_eq!(stem(None), "Arma 3 Preset");
+}
+
+#[test]
+fn steamcmd_script() {
+  let preset = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA_A3")
+    .local_mod("Tweaks")
+    .steam_mod(463939057u64, "ace")
+    .dlc(1021790u64, "Contact")
+    .build();
+  let script = preset.to_steamcmd_script(107410u64);
+  let lines = script.lines().collect::<Vec<&str>>();
+  assert_eq!(lines, ["workshop_download_item 107410 450814997", "workshop_download_item 107410 463939057"]);
+  assert!(script.ends_with('\n'));
+}
+
+#[test]
+fn every_output_format_renders() {
+  let preset = include_str!("fixtures/arma_preset.html").parse::<Preset>().unwrap();
+  let mut names = vec!["text", "html", "csv", "markdown"];
+  if cfg!(feature = "serde") {
+    names.push("json");
+  };
+
+  for name in names {
+    let format = name.parse::<OutputFormat>().unwrap();
+    let rendered = preset.render(format);
+    assert!(!rendered.trim().is_empty(), "{name}");
+    let lines = rendered.lines().collect::<Vec<&str>>();
+    match format {
+      OutputFormat::Text => {
+        assert_eq!(lines[0], "Arma 3 Preset: Example Preset");
+        assert_eq!(lines.len(), 6);
+        assert!(lines[1..].iter().all(|line| ["Steam: ", "Local: ", "DLC: "].iter().any(|p| line.starts_with(p))));
+      },
+      OutputFormat::Html => assert_eq!(rendered.parse::<Preset>().unwrap(), preset),
+      OutputFormat::Csv => {
+        assert_eq!(lines[0], "kind,id,display_name");
+        assert_eq!(lines.len(), 6);
+        assert!(lines.iter().all(|line| line.split(',').count() == 3));
+      },
+      OutputFormat::Markdown => {
+        assert_eq!(lines[0], "# Example Preset");
+        let rows = lines.iter().filter(|line| line.starts_with('|') && line.ends_with('|')).count();
+        assert_eq!(rows, 7);
+      },
+      #[cfg(feature = "serde")]
+      OutputFormat::Json => assert_eq!(Preset::from_json(&rendered).unwrap(), preset),
+      _ => unreachable!()
+    };
+  };
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "flate2")]
+
+use a3_preset_parser::Preset;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use std::io::Write;
+
+const ARMA_PRESET: &str = include_str!("fixtures/arma_preset.html");
+
+fn gzip(text: &str) -> Vec<u8> {
+  let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+  encoder.write_all(text.as_bytes()).unwrap();
+  encoder.finish().unwrap()
+}
+
+#[test]
+fn gz_reader() {
+  let expected = ARMA_PRESET.parse::<Preset>().unwrap();
+  assert_eq!(Preset::from_gz_reader(gzip(ARMA_PRESET).as_slice()).unwrap(), expected);
+}
+
+#[test]
+fn from_file_detects_gzip() {
+  let expected = ARMA_PRESET.parse::<Preset>().unwrap();
+  let dir = std::env::temp_dir().join(format!("a3-preset-parser-gzip-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+
+  let gz_path = dir.join("preset.html.gz");
+  let plain_path = dir.join("preset.html");
+  std::fs::write(&gz_path, gzip(ARMA_PRESET)).unwrap();
+  std::fs::write(&plain_path, ARMA_PRESET).unwrap();
+  let gz_preset = Preset::from_file(&gz_path);
+  let plain_preset = Preset::from_file(&plain_path);
+  std::fs::remove_dir_all(&dir).unwrap();
+
+  assert_eq!(gz_preset.unwrap(), expected);
+  assert_eq!(plain_preset.unwrap(), expected);
+}
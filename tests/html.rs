@@ -0,0 +1,61 @@
+use a3_preset_parser::{Game, Preset, PresetBuilder, PresetKind};
+
+const ARMA_PRESET: &str = include_str!("fixtures/arma_preset.html");
+const DAYZ_LIST: &str = include_str!("fixtures/dayz_list.html");
+
+#[test]
+fn html_round_trip() {
+  for document_text in [ARMA_PRESET, DAYZ_LIST] {
+    let preset = document_text.parse::<Preset>().unwrap();
+    let reparsed = preset.to_html().parse::<Preset>().unwrap();
+    assert_eq!(reparsed, preset);
+  };
+}
+
+#[test]
+fn html_file_round_trip() {
+  let preset = ARMA_PRESET.parse::<Preset>().unwrap();
+  let dir = std::env::temp_dir().join(format!("a3-preset-parser-html-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+
+  let html_path = dir.join("preset.html");
+  let summary_path = dir.join("preset.txt");
+  preset.to_html_file(&html_path).unwrap();
+  preset.to_summary_file(&summary_path).unwrap();
+  let reread = Preset::from_file(&html_path);
+  let summary = std::fs::read_to_string(&summary_path);
+  std::fs::remove_dir_all(&dir).unwrap();
+
+  assert_eq!(reread.unwrap(), preset);
+  assert_eq!(summary.unwrap(), preset.to_string());
+}
+
+#[test]
+fn html_meta_tags() {
+  let preset = PresetBuilder::new(Game::DayZ).kind(PresetKind::List).name("Server").build();
+  let html = preset.to_html();
+  assert!(html.contains("<meta name=\"dayz:Type\" content=\"list\" />"), "{html}");
+  assert!(html.contains("<meta name=\"dayz:PresetName\" content=\"Server\" />"), "{html}");
+  assert!(!html.contains("arma:"), "{html}");
+}
+
+#[test]
+fn html_escapes_names() {
+  let preset = PresetBuilder::new(Game::Arma).name("A & B").local_mod("<Tweaks> & \"Fixes\"").build();
+  let reparsed = preset.to_html().parse::<Preset>().unwrap();
+  assert_eq!(reparsed.preset_name.as_deref(), Some("A & B"));
+  assert_eq!(reparsed.local_mods[0].display_name, "<Tweaks> & \"Fixes\"");
+}
+
+#[test]
+fn html_keeps_interleaved_mod_order() {
+  let preset = ARMA_PRESET.parse::<Preset>().unwrap();
+  let html = preset.to_html();
+  let positions = ["CBA_A3", "Local Tweaks", "ace", "Server Keys"]
+    .map(|name| html.find(&format!(">{name}<")).unwrap());
+  assert!(positions.is_sorted(), "{html}");
+
+  let reparsed = html.parse::<Preset>().unwrap();
+  let orders = |preset: &Preset| preset.mods_in_order().map(|m| m.order()).collect::<Vec<_>>();
+  assert_eq!(orders(&reparsed), [Some(0), Some(1), Some(2), Some(3)]);
+}
@@ -0,0 +1,23 @@
+use a3_preset_parser::{Mod, Preset};
+
+const ARMA_PRESET: &str = include_str!("fixtures/arma_preset.html");
+
+#[test]
+fn into_mods_follows_document_order() {
+  let preset = ARMA_PRESET.parse::<Preset>().unwrap();
+  let names = preset.into_mods().into_iter()
+    .map(|m| match m {
+      Mod::Steam { name, .. } | Mod::Local { name, .. } | Mod::Dlc { name, .. } => name
+    })
+    .collect::<Vec<String>>();
+  assert_eq!(names, ["CBA_A3", "Local Tweaks", "ace", "Server Keys", "Contact"]);
+}
+
+#[test]
+fn mods_round_trip() {
+  let preset = ARMA_PRESET.parse::<Preset>().unwrap();
+  let restored = Preset::from_mods(preset.game, preset.clone().into_mods());
+  assert_eq!(restored.steam_mods, preset.steam_mods);
+  assert_eq!(restored.local_mods, preset.local_mods);
+  assert_eq!(restored.dlcs, preset.dlcs);
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "serde")]
+
+use a3_preset_parser::{AppId, Game, Preset, PresetKind, WorkshopId};
+
+#[test]
+fn json_without_kind_defaults_to_preset() {
+  let json = r#"{"game":"Arma","steam_mods":[{"display_name":"CBA_A3","id":450814997}]}"#;
+  let preset = Preset::from_json(json).unwrap();
+  assert_eq!(preset.game, Game::Arma);
+  assert_eq!(preset.kind, PresetKind::Preset);
+  assert_eq!(preset.steam_mods[0].id, 450814997);
+}
+
+#[test]
+fn json_round_trip() {
+  for document_text in [include_str!("fixtures/arma_preset.html"), include_str!("fixtures/dayz_list.html")] {
+    let preset = document_text.parse::<Preset>().unwrap();
+    assert_eq!(Preset::from_json(&preset.to_json()).unwrap(), preset);
+  };
+}
+
+#[test]
+fn json_skips_empty_fields() {
+  let json = Preset::new(Game::DayZ).to_json();
+  assert_eq!(json, r#"{"game":"DayZ","kind":"Preset"}"#);
+  assert_eq!(Preset::from_json(&json).unwrap(), Preset::new(Game::DayZ));
+}
+
+#[test]
+fn swifty_json() {
+  let preset = include_str!("fixtures/arma_preset.html").parse::<Preset>().unwrap();
+  let expected = r#"[{"name":"CBA_A3","workshopId":450814997},{"name":"ace","workshopId":463939057}]"#;
+  assert_eq!(preset.to_swifty_json(), expected);
+}
+
+#[test]
+fn ids_serialize_as_numbers() {
+  assert_eq!(serde_json::to_string(&WorkshopId(450814997)).unwrap(), "450814997");
+  assert_eq!(serde_json::to_string(&AppId(1021790)).unwrap(), "1021790");
+  assert_eq!(serde_json::from_str::<WorkshopId>("450814997").unwrap(), WorkshopId(450814997));
+  assert_eq!(serde_json::from_str::<AppId>("1021790").unwrap(), AppId(1021790));
+  assert!(serde_json::from_str::<WorkshopId>("\"450814997\"").is_err());
+}
+
+#[test]
+fn from_ids_to_json() {
+  let preset = Preset::from_ids(Game::Arma, [450814997u64, 463939057, 333310405]);
+  let expected = concat!(
+    r#"{"game":"Arma","kind":"Preset","steam_mods":["#,
+    r#"{"display_name":"450814997","id":450814997},"#,
+    r#"{"display_name":"463939057","id":463939057},"#,
+    r#"{"display_name":"333310405","id":333310405}]}"#
+  );
+  assert_eq!(preset.to_json(), expected);
+  assert_eq!(Preset::from_json(expected).unwrap(), preset);
+  assert!(preset.to_html().parse::<Preset>().unwrap().eq_unordered(&preset));
+}
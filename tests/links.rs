@@ -0,0 +1,102 @@
+use a3_preset_parser::{AppId, Error, ItemId, Preset, SteamHosts, WorkshopId, STEAM_STORE_APP_URL, STEAM_WORKSHOP_URL};
+
+fn document(mod_link: &str, dlc_link: &str) -> String {
+  format!(r#"<html>
+  <head>
+    <meta name="arma:Type" content="preset" />
+  </head>
+  <body>
+    <div class="mod-list">
+      <table>
+        <tr data-type="ModContainer">
+          <td data-type="DisplayName">CBA_A3</td>
+          <td><span class="from-steam">Steam</span></td>
+          <td><a href="{mod_link}" data-type="Link">{mod_link}</a></td>
+        </tr>
+      </table>
+    </div>
+    <div class="dlc-list">
+      <table>
+        <tr data-type="DlcContainer">
+          <td data-type="DisplayName">Contact</td>
+          <td><a href="{dlc_link}" data-type="Link">{dlc_link}</a></td>
+        </tr>
+      </table>
+    </div>
+  </body>
+</html>"#)
+}
+
+const MOD_LINK: &str = "https://steamcommunity.com/sharedfiles/filedetails/?id=450814997";
+const DLC_LINK: &str = "https://store.steampowered.com/app/1021790";
+
+fn workshop_id(mod_link: &str) -> Result<u64, Error> {
+  document(mod_link, DLC_LINK).parse::<Preset>().map(|preset| preset.steam_mods[0].id.0)
+}
+
+#[test]
+fn workshop_link_with_extra_query_params() {
+  assert_eq!(workshop_id(MOD_LINK).unwrap(), 450814997);
+  assert_eq!(workshop_id("https://steamcommunity.com/sharedfiles/filedetails/?searchtext=x&id=450814997").unwrap(), 450814997);
+  assert_eq!(workshop_id("https://steamcommunity.com/sharedfiles/filedetails/?id=450814997&searchtext=x").unwrap(), 450814997);
+  assert_eq!(workshop_id("https://steamcommunity.com/sharedfiles/filedetails?id=450814997#comments").unwrap(), 450814997);
+  assert!(matches!(
+    workshop_id("https://steamcommunity.com/sharedfiles/filedetails/?searchtext=x"),
+    Err(Error::InvalidItemLinkSteamWorkshop(..))
+  ));
+}
+
+fn app_id(dlc_link: &str) -> Result<u64, Error> {
+  document(MOD_LINK, dlc_link).parse::<Preset>().map(|preset| preset.dlcs[0].id.0)
+}
+
+#[test]
+fn app_link_with_name_slug() {
+  assert_eq!(app_id("https://store.steampowered.com/app/1227700").unwrap(), 1227700);
+  assert_eq!(app_id("https://store.steampowered.com/app/1227700/").unwrap(), 1227700);
+  assert_eq!(app_id("https://store.steampowered.com/app/1227700/Arma_3_Contact/").unwrap(), 1227700);
+  assert!(matches!(app_id("https://store.steampowered.com/app/Arma_3/"), Err(Error::InvalidItemLinkSteamApp(..))));
+}
+
+#[test]
+fn protocol_relative_and_www_links() {
+  for mod_link in [
+    "http://steamcommunity.com/sharedfiles/filedetails/?id=450814997",
+    "//steamcommunity.com/sharedfiles/filedetails/?id=450814997",
+    "https://www.steamcommunity.com/sharedfiles/filedetails/?id=450814997",
+    "//www.steamcommunity.com/sharedfiles/filedetails/?id=450814997"
+  ] {
+    assert_eq!(workshop_id(mod_link).unwrap(), 450814997, "{mod_link}");
+  };
+
+  for dlc_link in [
+    "http://store.steampowered.com/app/1021790",
+    "//store.steampowered.com/app/1021790",
+    "https://www.store.steampowered.com/app/1021790",
+    "//www.store.steampowered.com/app/1021790/Arma_3_Contact/"
+  ] {
+    assert_eq!(app_id(dlc_link).unwrap(), 1021790, "{dlc_link}");
+  };
+}
+
+#[test]
+fn url_formats() {
+  let preset = document(MOD_LINK, DLC_LINK).parse::<Preset>().unwrap();
+  assert_eq!(preset.steam_mods[0].steam_workshop_url(), "https://steamcommunity.com/sharedfiles/filedetails/?id=450814997");
+  assert_eq!(preset.dlcs[0].steam_store_url(), "https://store.steampowered.com/app/1021790");
+  assert_eq!(format!("{STEAM_WORKSHOP_URL}450814997"), preset.steam_mods[0].steam_workshop_url());
+  assert_eq!(format!("{STEAM_STORE_APP_URL}1021790"), preset.dlcs[0].steam_store_url());
+
+  let hosts = SteamHosts::new("workshop.mirror.example", "store.mirror.example");
+  assert_eq!(preset.steam_mods[0].steam_workshop_url_on(&hosts), "https://workshop.mirror.example/sharedfiles/filedetails/?id=450814997");
+  assert_eq!(preset.dlcs[0].steam_store_url_on(&hosts), "https://store.mirror.example/app/1021790");
+}
+
+#[test]
+fn id_display() {
+  assert_eq!(WorkshopId(450814997).to_string(), "450814997");
+  assert_eq!(AppId(1021790).to_string(), "1021790");
+  assert_eq!(ItemId::Workshop(WorkshopId(450814997)).to_string(), "450814997");
+  assert_eq!(format!("{:>9}", AppId(107410)), "   107410");
+  assert_eq!("450814997".parse::<WorkshopId>().unwrap(), WorkshopId(450814997));
+}
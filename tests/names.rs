@@ -0,0 +1,49 @@
+use a3_preset_parser::{Game, NameNormalization, PresetBuilder};
+#[cfg(feature = "unicode-normalization")]
+use a3_preset_parser::MergeOptions;
+
+#[test]
+fn ascii_lowercase_keeps_accents_distinct() {
+  let mut preset = PresetBuilder::new(Game::Arma).local_mod("Café Tweaks").local_mod("cafe tweaks").build();
+  assert_eq!(preset.dedupe_local_mods_with(NameNormalization::AsciiLowercase), 0);
+  assert_eq!(preset.local_mods.len(), 2);
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn fold_accents_merges_accented_names() {
+  assert_eq!(NameNormalization::FoldAccents.normalize(" Café "), "cafe");
+
+  let mut preset = PresetBuilder::new(Game::Arma).local_mod("Café Tweaks").local_mod("cafe tweaks").build();
+  assert_eq!(preset.dedupe_local_mods_with(NameNormalization::FoldAccents), 1);
+  assert_eq!(preset.local_mods[0].display_name, "Café Tweaks");
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn fold_accents_threads_through_operations() {
+  let mut preset = PresetBuilder::new(Game::Arma).local_mod("Café Tweaks").build();
+  let other = PresetBuilder::new(Game::Arma).local_mod("cafe tweaks").build();
+  assert!(preset.eq_unordered_with(&other, NameNormalization::FoldAccents));
+  assert!(!preset.eq_unordered(&other));
+
+  let mut merged = preset.clone();
+  let options = MergeOptions { normalization: NameNormalization::FoldAccents, ..MergeOptions::default() };
+  merged.merge_with_options(&other, options).unwrap();
+  assert_eq!(merged.local_mods.len(), 1);
+  merged.merge(&other).unwrap();
+  assert_eq!(merged.local_mods.len(), 2);
+
+  assert!(!preset.add_local_mod_unique_with("CAFE TWEAKS", NameNormalization::FoldAccents));
+  assert!(preset.remove_local_mod_with("cafe tweaks", NameNormalization::FoldAccents));
+  assert!(preset.local_mods.is_empty());
+}
+
+#[cfg(all(feature = "unicode-normalization", feature = "sha2"))]
+#[test]
+fn fold_accents_fingerprint() {
+  let a = PresetBuilder::new(Game::Arma).local_mod("Café Tweaks").build();
+  let b = PresetBuilder::new(Game::Arma).local_mod("cafe tweaks").build();
+  assert_eq!(a.fingerprint_with(NameNormalization::FoldAccents), b.fingerprint_with(NameNormalization::FoldAccents));
+  assert_ne!(a.fingerprint(), b.fingerprint());
+}
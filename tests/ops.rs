@@ -0,0 +1,359 @@
+use a3_preset_parser::{DiffOptions, Error, Game, ItemId, ModRename, NameResolution, Preset, PresetBuilder, PresetLocalMod, PresetMod, PresetSteamMod};
+
+use std::collections::HashSet;
+
+fn arma_and_dayz() -> (Preset, Preset) {
+  let arma = PresetBuilder::new(Game::Arma).steam_mod(450814997u64, "CBA_A3").build();
+  let dayz = PresetBuilder::new(Game::DayZ).steam_mod(1559212036u64, "Community Framework").build();
+  (arma, dayz)
+}
+
+#[test]
+fn mixed_games_are_rejected() {
+  let (mut arma, dayz) = arma_and_dayz();
+  let is_mismatch = |err: Error| matches!(err, Error::GameMismatch { a: Game::Arma, b: Game::DayZ });
+  assert_eq!(arma.assert_same_game(&arma).unwrap(), Game::Arma);
+  assert!(is_mismatch(arma.assert_same_game(&dayz).unwrap_err()));
+  assert!(is_mismatch(arma.diff(&dayz).unwrap_err()));
+  assert!(is_mismatch(arma.union(&dayz).unwrap_err()));
+  assert!(is_mismatch(arma.intersection(&dayz).unwrap_err()));
+  assert!(is_mismatch(arma.difference(&dayz).unwrap_err()));
+  assert!(is_mismatch(arma.merge(&dayz).unwrap_err()));
+  assert!(is_mismatch(Preset::diff_many(&[("arma", &arma), ("also arma", &arma), ("dayz", &dayz)]).unwrap_err()));
+  assert!(!arma.is_subset_of(&dayz));
+}
+
+#[test]
+fn set_operations_clear_document_info() {
+  let a = include_str!("fixtures/arma_preset.html").parse::<Preset>().unwrap();
+  let b = PresetBuilder::new(Game::Arma).steam_mod(450814997u64, "CBA_A3").steam_mod(333310405u64, "Enhanced Movement").build();
+  assert!(a.generated_by.is_some() && !a.extra_meta.is_empty());
+
+  for preset in [a.union(&b).unwrap(), a.intersection(&b).unwrap(), a.difference(&b).unwrap()] {
+    assert_eq!(preset.preset_name, None);
+    assert!(preset.extra_meta.is_empty());
+    assert_eq!(preset.generated_by, None);
+    assert_eq!(preset.generated_at, None);
+  };
+
+  let ids = |preset: Preset| preset.steam_mods.iter().map(|m| m.id.0).collect::<Vec<u64>>();
+  assert_eq!(ids(a.union(&b).unwrap()), [450814997, 463939057, 333310405]);
+  assert_eq!(ids(a.intersection(&b).unwrap()), [450814997]);
+  assert_eq!(ids(a.difference(&b).unwrap()), [463939057]);
+}
+
+#[test]
+fn missing_and_extra_ids() {
+  let preset = include_str!("fixtures/arma_preset.html").parse::<Preset>().unwrap();
+  assert_eq!(preset.missing_from([450814997u64, 333310405, 463939057, 843577117]), [333310405, 843577117]);
+  assert!(preset.missing_from([450814997u64]).is_empty());
+  assert_eq!(preset.missing_from([1021790u64, 1681170, 843577117]), [1681170, 843577117]);
+  assert_eq!(preset.missing_dlcs_from([1021790u64, 1681170]), [1681170]);
+  assert_eq!(preset.missing_dlcs_from([450814997u64]), [450814997]);
+
+  let extra = preset.extra_beyond([450814997u64]).into_iter().map(|m| m.id.0).collect::<Vec<u64>>();
+  assert_eq!(extra, [463939057]);
+  assert!(preset.extra_beyond([450814997u64, 463939057]).is_empty());
+}
+
+#[test]
+fn retain_by_id() {
+  let mut preset = include_str!("fixtures/arma_preset.html").parse::<Preset>().unwrap();
+  preset.retain_by_id(&HashSet::from([463939057, 1021790]));
+  assert_eq!(preset.steam_mods.len(), 1);
+  assert_eq!(preset.steam_mods[0].id, 463939057);
+  assert_eq!(preset.dlcs.len(), 1);
+  assert_eq!(preset.dlcs[0].id, 1021790);
+  assert_eq!(preset.local_mods.len(), 2);
+}
+
+#[test]
+fn retain_by_id_per_kind() {
+  let mut preset = include_str!("fixtures/arma_preset.html").parse::<Preset>().unwrap();
+  preset.retain_steam_mods_by_id([463939057u64]);
+  preset.retain_dlcs_by_id(Vec::<u64>::new());
+  assert_eq!(preset.steam_mods.len(), 1);
+  assert_eq!(preset.steam_mods[0].id, 463939057);
+  assert_eq!(preset.local_mods.len(), 2);
+  assert!(preset.dlcs.is_empty());
+}
+
+#[test]
+fn canonicalize_is_order_independent() {
+  let a = PresetBuilder::new(Game::Arma)
+    .steam_mod(463939057u64, "ace")
+    .local_mod("  Server   Keys ")
+    .steam_mod(450814997u64, "CBA_A3")
+    .local_mod("Local Tweaks")
+    .dlc(1021790u64, "Contact")
+    .steam_mod(463939057u64, "ace")
+    .build();
+  let b = PresetBuilder::new(Game::Arma)
+    .dlc(1021790u64, "Contact")
+    .local_mod("Local Tweaks")
+    .steam_mod(450814997u64, "CBA_A3")
+    .local_mod("Server Keys")
+    .steam_mod(463939057u64, "ace")
+    .build();
+
+  let (a, b) = (a.canonicalize(), b.canonicalize());
+  assert_eq!(a, b);
+  assert_eq!(a.to_html(), b.to_html());
+  let ids = a.steam_mods.iter().map(|m| m.id.0).collect::<Vec<u64>>();
+  assert_eq!(ids, [450814997, 463939057]);
+  let names = a.local_mods.iter().map(|m| m.display_name.as_str()).collect::<Vec<&str>>();
+  assert_eq!(names, ["Local Tweaks", "Server Keys"]);
+}
+
+#[test]
+fn canonicalize_ignores_export_metadata() {
+  let mut a = PresetBuilder::new(Game::Arma).steam_mod(450814997u64, "CBA_A3").build();
+  let mut b = a.clone();
+  a.generated_by = Some("Arma 3 Launcher - https://arma3.com".to_owned());
+  a.generated_at = Some("2024-01-01 12:00".to_owned());
+  b.generated_at = Some("2024-06-30 08:15".to_owned());
+
+  let (a, b) = (a.canonicalize(), b.canonicalize());
+  assert_eq!(a, b);
+  assert_eq!(a.to_html(), b.to_html());
+  assert_eq!(a.generated_at, None);
+  assert_eq!(a.generated_by, None);
+}
+
+#[test]
+fn diff_many_three_presets() {
+  let a = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA_A3")
+    .steam_mod(463939057u64, "ace")
+    .dlc(1021790u64, "Contact")
+    .local_mod("Tweaks")
+    .build();
+  let b = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA_A3")
+    .steam_mod(333310405u64, "Enhanced Movement")
+    .local_mod("tweaks")
+    .build();
+  let c = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA")
+    .steam_mod(463939057u64, "ace")
+    .dlc(1021790u64, "Contact")
+    .local_mod("Keys")
+    .build();
+
+  let multi = Preset::diff_many(&[("a", &a), ("b", &b), ("c", &c)]).unwrap();
+  assert_eq!(multi.names, ["a", "b", "c"]);
+  let ids = |mods: &[PresetSteamMod]| mods.iter().map(|m| m.id.0).collect::<Vec<u64>>();
+  assert_eq!(ids(&multi.steam_mods), [333310405, 450814997, 463939057]);
+  assert_eq!(multi.steam_mods[1].display_name, "CBA_A3");
+  assert_eq!(multi.steam_presence[&463939057u64.into()], [true, false, true]);
+  assert_eq!(ids(&multi.steam_common), [450814997]);
+  assert_eq!(multi.steam_unique.iter().map(|mods| ids(mods)).collect::<Vec<_>>(), [vec![], vec![333310405], vec![]]);
+
+  assert_eq!(multi.dlc_presence[&1021790u64.into()], [true, false, true]);
+  assert!(multi.dlc_common.is_empty());
+  assert!(multi.dlc_unique.iter().all(Vec::is_empty));
+
+  let names = |mods: &[PresetLocalMod]| mods.iter().map(|m| m.display_name.clone()).collect::<Vec<String>>();
+  assert_eq!(names(&multi.local_mods), ["Keys", "Tweaks"]);
+  assert_eq!(multi.local_presence["tweaks"], [true, true, false]);
+  assert!(multi.local_common.is_empty());
+  assert_eq!(multi.local_unique.iter().map(|mods| names(mods)).collect::<Vec<_>>(), [vec![], vec![], vec!["Keys".to_owned()]]);
+
+  let expected = "\
+Steam Mod                     | a | b | c
+Enhanced Movement (333310405) |   | x |
+CBA_A3 (450814997)            | x | x | x
+ace (463939057)               | x |   | x
+
+DLC               | a | b | c
+Contact (1021790) | x |   | x
+
+Local Mod | a | b | c
+Keys      |   |   | x
+Tweaks    | x | x |";
+  // Cells are padded to the width of their column's name, so compare without trailing whitespace.
+  let table = multi.to_string().lines().map(str::trim_end).collect::<Vec<&str>>().join("\n");
+  assert_eq!(table, expected);
+}
+
+#[test]
+fn diff_matches_local_to_steam_by_name() {
+  let a = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA_A3")
+    .steam_mod(463939057u64, "ace")
+    .build();
+  let b = PresetBuilder::new(Game::Arma)
+    .local_mod(" cba_a3")
+    .local_mod("ACE Compat")
+    .build();
+
+  let diff = a.diff(&b).unwrap();
+  assert_eq!(diff.steam_only_a.len(), 2);
+  assert_eq!(diff.local_only_b.len(), 2);
+
+  let options = DiffOptions { match_local_to_steam_by_name: true, ..DiffOptions::default() };
+  let diff = a.diff_with_options(&b, options).unwrap();
+  let names = |mods: &[&PresetSteamMod]| mods.iter().map(|m| m.display_name.clone()).collect::<Vec<String>>();
+  assert_eq!(names(&diff.steam_shared), ["CBA_A3"]);
+  assert_eq!(names(&diff.steam_only_a), ["ace"]);
+  assert!(diff.local_shared.is_empty());
+  assert_eq!(diff.local_only_b.iter().map(|m| m.display_name.as_str()).collect::<Vec<&str>>(), ["ACE Compat"]);
+
+  // Matching works the same way with the local mod on the other side.
+  let diff = b.diff_with_options(&a, options).unwrap();
+  assert_eq!(diff.local_shared.iter().map(|m| m.display_name.as_str()).collect::<Vec<&str>>(), [" cba_a3"]);
+  assert_eq!(diff.local_only_a.iter().map(|m| m.display_name.as_str()).collect::<Vec<&str>>(), ["ACE Compat"]);
+  assert_eq!(names(&diff.steam_only_b), ["ace"]);
+}
+
+#[test]
+fn changes_since() {
+  let previous = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA_A3")
+    .steam_mod(463939057u64, "ACE")
+    .local_mod("Old Tweaks")
+    .build();
+  let current = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA_A3")
+    .steam_mod(463939057u64, "ace")
+    .steam_mod(333310405u64, "Enhanced Movement")
+    .build();
+
+  let changes = current.changes_since(&previous).unwrap();
+  assert_eq!(changes.added, [PresetMod::Steam(current.steam_mods[2].clone())]);
+  assert_eq!(changes.removed, [PresetMod::Local(previous.local_mods[0].clone())]);
+  assert_eq!(changes.renamed, [ModRename { id: 463939057u64.into(), old_name: "ACE".to_owned(), new_name: "ace".to_owned() }]);
+  assert_eq!(changes.to_string(), "\
++ https://steamcommunity.com/sharedfiles/filedetails/?id=333310405: Enhanced Movement
+- Old Tweaks
+~ https://steamcommunity.com/sharedfiles/filedetails/?id=463939057: ACE -> ace
+");
+
+  assert!(current.changes_since(&current).unwrap().is_empty());
+}
+
+#[test]
+fn merge_name_resolution() {
+  let a = PresetBuilder::new(Game::Arma).steam_mod(463939057u64, "ACE3").dlc(1021790u64, "Contact (Platform)").build();
+  let b = PresetBuilder::new(Game::Arma).steam_mod(463939057u64, "Advanced Combat Environment 3").dlc(1021790u64, "Arma 3 Contact").build();
+  let c = PresetBuilder::new(Game::Arma).steam_mod(463939057u64, "ACE").dlc(1021790u64, "Contact").build();
+
+  let merged = |resolution: NameResolution| {
+    let mut preset = a.clone();
+    preset.merge_with(&b, resolution).unwrap();
+    preset.merge_with(&c, resolution).unwrap();
+    assert_eq!((preset.steam_mods.len(), preset.dlcs.len()), (1, 1));
+    (preset.steam_mods[0].display_name.clone(), preset.dlcs[0].display_name.clone())
+  };
+
+  assert_eq!(merged(NameResolution::FirstSeen), ("ACE3".to_owned(), "Contact (Platform)".to_owned()));
+  assert_eq!(merged(NameResolution::Longest), ("Advanced Combat Environment 3".to_owned(), "Contact (Platform)".to_owned()));
+  assert_eq!(merged(NameResolution::Lexicographically), ("ACE".to_owned(), "Arma 3 Contact".to_owned()));
+
+  let mut preset = a.clone();
+  preset.merge(&b).unwrap();
+  assert_eq!(preset, a);
+}
+
+#[test]
+fn estimate_size_keeps_id_kinds_apart() {
+  let preset = PresetBuilder::new(Game::Arma)
+    .steam_mod(1021790u64, "Mod with a DLC's id")
+    .dlc(1021790u64, "Contact")
+    .steam_mod(450814997u64, "CBA_A3")
+    .build();
+  let estimate = preset.estimate_size(|id| match id {
+    ItemId::Workshop(id) if id == 1021790 => Some(10),
+    ItemId::App(_) => Some(100),
+    ItemId::Workshop(_) => None
+  });
+  assert_eq!(estimate.total_bytes, 110);
+  assert_eq!(estimate.known_count, 2);
+  assert_eq!(estimate.unknown_count, 1);
+}
+
+#[test]
+fn reclassify_local_as_steam() {
+  let mut preset = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA_A3")
+    .local_mod("@463939057")
+    .local_mod("Tweaks")
+    .local_mod(" 450814997 ")
+    .build();
+  assert_eq!(preset.reclassify_local_as_steam(), 2);
+
+  let steam_mods = preset.steam_mods.iter().map(|m| (m.id.0, m.display_name.as_str())).collect::<Vec<_>>();
+  assert_eq!(steam_mods, [(450814997, "CBA_A3"), (463939057, "@463939057")]);
+  let local_names = preset.local_mods.iter().map(|m| m.display_name.as_str()).collect::<Vec<&str>>();
+  assert_eq!(local_names, ["Tweaks"]);
+}
+
+#[test]
+fn contains_lookups() {
+  let preset = include_str!("fixtures/arma_preset.html").parse::<Preset>().unwrap();
+  assert!(preset.contains_mod_named("cba_a3"));
+  assert!(preset.contains_mod_named("  LOCAL TWEAKS "));
+  assert!(!preset.contains_mod_named("Contact"));
+  assert!(!preset.contains_mod_named("CBA"));
+
+  assert!(preset.contains_steam_id(450814997u64));
+  assert!(!preset.contains_steam_id(333310405u64));
+  assert!(!preset.contains_steam_id(1021790u64));
+  assert!(preset.contains_dlc_id(1021790u64));
+  assert!(!preset.contains_dlc_id(1681170u64));
+  assert!(!preset.contains_dlc_id(450814997u64));
+}
+
+#[test]
+fn split_by_origin_recombines() {
+  let preset = include_str!("fixtures/arma_preset.html").parse::<Preset>().unwrap();
+  let (mut steam, local) = preset.split_by_origin();
+  assert!(steam.local_mods.is_empty());
+  assert!(local.steam_mods.is_empty() && local.dlcs.is_empty());
+  assert_eq!(steam.preset_name, preset.preset_name);
+  assert_eq!(local.preset_name, preset.preset_name);
+
+  steam.merge(&local).unwrap();
+  assert!(steam.eq_unordered(&preset));
+  assert_eq!(steam.steam_mods, preset.steam_mods);
+  assert_eq!(steam.dlcs, preset.dlcs);
+  let local_names = |p: &Preset| p.local_mods.iter().map(|m| m.display_name.clone()).collect::<Vec<String>>();
+  assert_eq!(local_names(&steam), local_names(&preset));
+}
+
+#[test]
+fn is_subset_of() {
+  let base = include_str!("fixtures/arma_preset.html").parse::<Preset>().unwrap();
+  let mut trimmed = base.clone();
+  trimmed.retain_steam_mods(|m| m.id == 450814997);
+  trimmed.retain_local_mods(|m| m.display_name == "Server Keys");
+  trimmed.local_mods[0].display_name = "SERVER KEYS".to_owned();
+
+  assert!(trimmed.is_subset_of(&base));
+  assert!(!base.is_subset_of(&trimmed));
+  assert!(base.is_subset_of(&base.clone()));
+
+  let mut extra = trimmed.clone();
+  extra.add_dlc(1681170u64, "Western Sahara");
+  assert!(!extra.is_subset_of(&base));
+  let mut extra = trimmed.clone();
+  extra.add_local_mod("Unknown Mod");
+  assert!(!extra.is_subset_of(&base));
+}
+
+#[test]
+fn group_steam_mods_by_first_letter() {
+  let preset = PresetBuilder::new(Game::Arma)
+    .steam_mod(1u64, "ace")
+    .steam_mod(2u64, "CBA_A3")
+    .steam_mod(3u64, "Achilles")
+    .steam_mod(4u64, "cup Terrains")
+    .steam_mod(5u64, "3den Enhanced")
+    .steam_mod(6u64, "")
+    .build();
+  let groups = preset.group_steam_mods_by(|m| m.display_name.chars().next().map(|c| c.to_ascii_uppercase()));
+  let groups = groups.into_iter()
+    .map(|(key, mods)| (key, mods.into_iter().map(|m| m.id.0).collect::<Vec<u64>>()))
+    .collect::<Vec<_>>();
+  assert_eq!(groups, [(None, vec![6]), (Some('3'), vec![5]), (Some('A'), vec![1, 3]), (Some('C'), vec![2, 4])]);
+}
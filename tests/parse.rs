@@ -0,0 +1,265 @@
+use a3_preset_parser::{Error, Game, Html, ModRef, Preset, PresetKind, PresetParser, MAX_NESTING_DEPTH};
+
+const ARMA_PRESET: &str = include_str!("fixtures/arma_preset.html");
+// Laid out like a DayZ launcher mod list export, with the DayZ meta namespace and no preset name.
+const DAYZ_LIST: &str = include_str!("fixtures/dayz_list.html");
+
+#[test]
+fn generation_info_from_header_comment() {
+  let preset = ARMA_PRESET.parse::<Preset>().unwrap();
+  assert_eq!(preset.generated_by.as_deref(), Some("Arma 3 Launcher: https://arma3.com"));
+  assert_eq!(preset.generated_at, None);
+}
+
+#[test]
+fn generation_info_absent() {
+  let document_text = ARMA_PRESET.replace("<!--Created by Arma 3 Launcher: https://arma3.com-->", "")
+    .replace("<meta name=\"generator\" content=\"Arma 3 Launcher - https://arma3.com\" />", "");
+  let preset = document_text.parse::<Preset>().unwrap();
+  assert_eq!(preset.generated_by, None);
+  assert_eq!(preset.generated_at, None);
+}
+
+#[test]
+fn deeply_nested_document() {
+  // Header comments are only looked for among the top level nodes, `<html>` and `<head>`,
+  // so the rest of the document, however deeply nested, is never walked for them.
+  let depth = 1000;
+  let document_text = format!(
+    "<html><!--Created by Tests--><head><meta name=\"arma:Type\" content=\"preset\" /></head><body>{}{}{}</body></html>",
+    "<div><!--Not a header-->".repeat(depth), "</div>".repeat(depth), fixture_fragment("mod-list")
+  );
+
+  let preset = Preset::from_document(&Html::parse_document(&document_text)).unwrap();
+  assert_eq!(preset.generated_by.as_deref(), Some("Tests"));
+  let expected = ARMA_PRESET.parse::<Preset>().unwrap();
+  assert_eq!(preset.steam_mods, expected.steam_mods);
+  assert_eq!(preset.local_mods, expected.local_mods);
+  assert!(preset.dlcs.is_empty());
+}
+
+#[test]
+fn items_are_in_document_order() {
+  let preset = ARMA_PRESET.parse::<Preset>().unwrap();
+  let steam_mods = preset.steam_mods.iter().map(|m| (m.id.0, m.order)).collect::<Vec<_>>();
+  assert_eq!(steam_mods, [(450814997, Some(0)), (463939057, Some(2))]);
+  let local_mods = preset.local_mods.iter().map(|m| (m.display_name.as_str(), m.order)).collect::<Vec<_>>();
+  assert_eq!(local_mods, [("Local Tweaks", Some(1)), ("Server Keys", Some(3))]);
+  let dlcs = preset.dlcs.iter().map(|m| m.id.0).collect::<Vec<_>>();
+  assert_eq!(dlcs, [1021790]);
+
+  let mods = preset.mods_in_order()
+    .map(|m| match m {
+      ModRef::Steam(m) => m.display_name.as_str(),
+      ModRef::Local(m) => m.display_name.as_str()
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(mods, ["CBA_A3", "Local Tweaks", "ace", "Server Keys"]);
+}
+
+#[test]
+fn dayz_list() {
+  let preset = DAYZ_LIST.parse::<Preset>().unwrap();
+  assert_eq!(preset.game, Game::DayZ);
+  assert_eq!(preset.kind, PresetKind::List);
+  assert_eq!(preset.preset_name, None);
+
+  let steam_mods = preset.steam_mods.iter().map(|m| (m.id.0, m.display_name.as_str())).collect::<Vec<_>>();
+  assert_eq!(steam_mods, [(1559212036, "Community Framework"), (1564026768, "Community-Online-Tools")]);
+  let local_mods = preset.local_mods.iter().map(|m| m.display_name.as_str()).collect::<Vec<_>>();
+  assert_eq!(local_mods, ["@MyServerMod"]);
+  let dlcs = preset.dlcs.iter().map(|m| (m.id.0, m.display_name.as_str())).collect::<Vec<_>>();
+  assert_eq!(dlcs, [(1151700, "Livonia")]);
+  assert_eq!(preset.generated_by.as_deref(), Some("DayZ Launcher: https://dayz.com"));
+}
+
+#[test]
+fn best_effort_accepts_unknown_type() {
+  let document_text = DAYZ_LIST.replace("content=\"list\"", "content=\"modlist\"");
+  assert!(matches!(document_text.parse::<Preset>(), Err(Error::InvalidPresetTypeValue(value)) if value == "modlist"));
+
+  let preset = Preset::parse_best_effort(&document_text).unwrap();
+  assert_eq!(preset.game, Game::DayZ);
+  assert_eq!(preset.kind, PresetKind::Preset);
+}
+
+#[test]
+fn best_effort_infers_game_from_preset_name() {
+  let document_text = ARMA_PRESET.replace("<meta name=\"arma:Type\" content=\"preset\" />", "");
+  assert!(matches!(document_text.parse::<Preset>(), Err(Error::SelectorFailedPresetType(..))));
+
+  let preset = Preset::parse_best_effort(&document_text).unwrap();
+  assert_eq!(preset.game, Game::Arma);
+  assert_eq!(preset.kind, PresetKind::Preset);
+  assert_eq!(preset.preset_name.as_deref(), Some("Example Preset"));
+  assert_eq!(preset.steam_mods.len(), 2);
+}
+
+/// The `<div>` with the given class in the Arma fixture, as if it were copied out on its own.
+fn fixture_fragment(class: &str) -> &'static str {
+  let start = ARMA_PRESET.find(&format!("<div class=\"{class}\">")).unwrap();
+  let end = start + ARMA_PRESET[start..].find("</div>").unwrap() + "</div>".len();
+  &ARMA_PRESET[start..end]
+}
+
+#[test]
+fn from_element_finds_nested_lists() {
+  let document_text = format!(
+    "<html><body><div id=\"page\"><div class=\"wrapper\">{}{}</div></div></body></html>",
+    fixture_fragment("mod-list"), fixture_fragment("dlc-list")
+  );
+  let document = Html::parse_document(&document_text);
+  assert!(matches!(Preset::from_document(&document), Err(Error::NotAPreset)));
+
+  let preset = Preset::from_element(document.root_element(), Game::Arma).unwrap();
+  let expected = ARMA_PRESET.parse::<Preset>().unwrap();
+  assert_eq!(preset.game, Game::Arma);
+  assert_eq!(preset.preset_name, None);
+  assert_eq!(preset.steam_mods, expected.steam_mods);
+  assert_eq!(preset.local_mods, expected.local_mods);
+  assert_eq!(preset.dlcs, expected.dlcs);
+}
+
+#[test]
+fn fragments_are_opt_in() {
+  let fragment = fixture_fragment("mod-list");
+  assert!(matches!(fragment.parse::<Preset>(), Err(Error::NotAPreset)));
+  assert!(matches!(PresetParser::new().parse(fragment), Err(Error::NotAPreset)));
+}
+
+#[test]
+fn mod_list_fragment() {
+  let preset = PresetParser::new().fragments(true).parse(fixture_fragment("mod-list")).unwrap();
+  assert_eq!(preset.game, Game::Arma);
+  assert_eq!(preset.kind, PresetKind::Preset);
+  assert_eq!(preset.preset_name, None);
+  assert_eq!(preset.steam_mods.len(), 2);
+  assert_eq!(preset.local_mods.len(), 2);
+  assert!(preset.dlcs.is_empty());
+}
+
+#[test]
+fn dlc_list_fragment() {
+  let preset = PresetParser::new().fragments(true).parse(fixture_fragment("dlc-list")).unwrap();
+  assert_eq!(preset.game, Game::Arma);
+  assert!(preset.steam_mods.is_empty() && preset.local_mods.is_empty());
+  assert_eq!(preset.dlcs[0].id, 1021790);
+}
+
+#[test]
+fn fragment_without_rows() {
+  let parser = PresetParser::new().fragments(true);
+  assert!(matches!(parser.parse("<div class=\"mod-list\"><table></table></div>"), Err(Error::NotAPreset)));
+}
+
+#[test]
+fn names_are_entity_decoded_and_joined() {
+  let name = |markup: &str| {
+    let document_text = ARMA_PRESET.replace(">CBA_A3<", &format!(">{markup}<"));
+    document_text.parse::<Preset>().unwrap().steam_mods[0].display_name.clone()
+  };
+
+  assert_eq!(name("CBA &amp; ACE"), "CBA & ACE");
+  assert_eq!(name("Bob&#39;s Tweaks"), "Bob's Tweaks");
+  assert_eq!(name("CBA <span>&amp;</span> ACE"), "CBA & ACE");
+  assert_eq!(name("CBA<!-- comment --> &amp;<b> ACE</b>"), "CBA & ACE");
+
+  let document_text = ARMA_PRESET.replace("content=\"Example Preset\"", "content=\"Ops &amp; Training\"");
+  assert_eq!(document_text.parse::<Preset>().unwrap().preset_name.as_deref(), Some("Ops & Training"));
+}
+
+#[test]
+fn leading_bom_is_ignored() {
+  let document_text = format!("\u{feff}{ARMA_PRESET}");
+  let expected = ARMA_PRESET.parse::<Preset>().unwrap();
+  assert_eq!(document_text.parse::<Preset>().unwrap(), expected);
+  assert_eq!(Preset::from_reader(document_text.as_bytes()).unwrap(), expected);
+}
+
+#[test]
+fn nesting_depth_limit() {
+  let nested = |open: &str, close: &str, depth: usize| {
+    ARMA_PRESET.replacen("<body>", &format!("<body>{}{}", open.repeat(depth), close.repeat(depth)), 1)
+  };
+
+  assert!(nested("<div>", "</div>", MAX_NESTING_DEPTH - 8).parse::<Preset>().is_ok());
+  assert!(matches!(nested("<div>", "</div>", MAX_NESTING_DEPTH).parse::<Preset>(), Err(Error::NestingTooDeep)));
+  // Non-void elements are not closed by a trailing slash, nor by end tags for elements that are not open.
+  assert!(matches!(nested("<div />", "", MAX_NESTING_DEPTH).parse::<Preset>(), Err(Error::NestingTooDeep)));
+  assert!(matches!(nested("<div></span>", "", MAX_NESTING_DEPTH).parse::<Preset>(), Err(Error::NestingTooDeep)));
+  assert!(matches!(Preset::parse_lenient(&nested("<b>", "", MAX_NESTING_DEPTH)), (None, errors) if errors.len() == 1));
+
+  // Void elements, comments and the contents of raw text elements do not nest.
+  assert!(nested("<br><img src=\"a>b\"><!--<div>-->", "", MAX_NESTING_DEPTH).parse::<Preset>().is_ok());
+  assert!(nested("<script>if (a<div) {}</script><style>div > p {}</style>", "", MAX_NESTING_DEPTH).parse::<Preset>().is_ok());
+}
+
+#[test]
+fn meta_names_are_case_insensitive() {
+  let expected = ARMA_PRESET.parse::<Preset>().unwrap();
+  for (type_name, preset_name) in [("Arma:Type", "Arma:PresetName"), ("arma:type", "ARMA:PRESETNAME")] {
+    let document_text = ARMA_PRESET
+      .replace("name=\"arma:Type\"", &format!("name=\"{type_name}\""))
+      .replace("name=\"arma:PresetName\"", &format!("name=\"{preset_name}\""));
+    assert!(document_text.contains(type_name) && document_text.contains(preset_name));
+    assert_eq!(document_text.parse::<Preset>().unwrap(), expected);
+  };
+
+  let expected = DAYZ_LIST.parse::<Preset>().unwrap();
+  for type_name in ["DayZ:Type", "DAYZ:TYPE", "dayz:type"] {
+    let document_text = DAYZ_LIST.replace("name=\"dayz:Type\"", &format!("name=\"{type_name}\""));
+    assert!(document_text.contains(type_name));
+    let preset = document_text.parse::<Preset>().unwrap();
+    assert_eq!((preset.game, preset.kind), (Game::DayZ, PresetKind::List));
+    assert_eq!(preset, expected);
+  };
+}
+
+#[test]
+fn game_from_str_aliases() {
+  for alias in ["Arma 3", "arma 3", "Arma", "ARMA", "arma3", "Arma3", "a3", "A3", " arma "] {
+    assert_eq!(alias.parse::<Game>().unwrap(), Game::Arma, "{alias:?}");
+  };
+  for alias in ["DayZ", "dayz", "DAYZ", " DayZ "] {
+    assert_eq!(alias.parse::<Game>().unwrap(), Game::DayZ, "{alias:?}");
+  };
+  for game in [Game::Arma, Game::DayZ] {
+    assert_eq!(game.to_string().parse::<Game>().unwrap(), game);
+  };
+
+  for unknown in ["", "Arma 2", "a 3", "day z", "Reforger"] {
+    assert!(matches!(unknown.parse::<Game>(), Err(Error::InvalidGameName(name)) if name == unknown));
+  };
+}
+
+#[test]
+fn display_names_collapse_whitespace() {
+  let document_text = ARMA_PRESET.replacen(
+    "<td data-type=\"DisplayName\">ace</td>",
+    "<td data-type=\"DisplayName\">\n\t\t  ACE3\n\tAdvanced\t\tCombat \n Environment\t\n</td>",
+    1
+  );
+  let preset = document_text.parse::<Preset>().unwrap();
+  assert_eq!(preset.steam_mods[1].display_name, "ACE3 Advanced Combat Environment");
+}
+
+#[test]
+fn item_origin_inference() {
+  let span_absent = ARMA_PRESET
+    .replacen("<span class=\"from-steam\">Steam</span>", "", 1)
+    .replacen("<span class=\"from-local\">Local</span>", "", 1);
+  let preset = span_absent.parse::<Preset>().unwrap();
+  assert_eq!(preset, ARMA_PRESET.parse::<Preset>().unwrap());
+  assert_eq!(preset.steam_mods[0].display_name, "CBA_A3");
+  assert_eq!(preset.local_mods[0].display_name, "Local Tweaks");
+
+  let span_present = ARMA_PRESET.replacen(
+    "<span class=\"from-steam\">Steam</span>\n          </td>\n          <td>\n            <a href=\"https://steamcommunity.com/sharedfiles/filedetails/?id=450814997\"",
+    "<span class=\"from-local\">Local</span>\n          </td>\n          <td>\n            <a href=\"https://steamcommunity.com/sharedfiles/filedetails/?id=450814997\"",
+    1
+  );
+  assert_ne!(span_present, ARMA_PRESET);
+  let preset = span_present.parse::<Preset>().unwrap();
+  assert!(preset.steam_mods.iter().all(|m| m.id != 450814997));
+  assert_eq!(preset.local_mods[0].display_name, "CBA_A3");
+}
@@ -0,0 +1,85 @@
+use a3_preset_parser::{Error, Preset, PresetParser, SteamHosts, Warning};
+
+const ARMA_PRESET: &str = include_str!("fixtures/arma_preset.html");
+
+#[test]
+fn fixture_parses_clean() {
+  let outcome = PresetParser::new().parse_with_warnings(ARMA_PRESET).unwrap();
+  assert!(outcome.is_clean());
+  assert_eq!(outcome.preset, ARMA_PRESET.parse().unwrap());
+}
+
+#[test]
+fn warnings_for_recovered_rows() {
+  let document_text = ARMA_PRESET
+    .replacen("filedetails/?id=450814997\"", "filedetails/?id=450814997&amp;searchtext=cba\"", 1)
+    .replacen("<td data-type=\"DisplayName\">ace</td>", "<td data-type=\"DisplayName\">  ace\n  </td>", 1)
+    .replacen("<span class=\"from-steam\">Steam</span>", "", 2);
+  let outcome = PresetParser::new().parse_with_warnings(&document_text).unwrap();
+  assert!(!outcome.is_clean());
+  assert!(outcome.errors.is_empty());
+  assert_eq!(outcome.warnings, [
+    Warning::InferredOrigin { index: 0, origin: "from-steam".to_owned() },
+    Warning::ExtraQueryParams {
+      index: 0,
+      link: "https://steamcommunity.com/sharedfiles/filedetails/?id=450814997&searchtext=cba".to_owned()
+    },
+    Warning::NormalizedModName { index: 2, original: "  ace\n  ".to_owned(), normalized: "ace".to_owned() },
+    Warning::InferredOrigin { index: 2, origin: "from-steam".to_owned() }
+  ]);
+  assert_eq!(outcome.preset, ARMA_PRESET.parse().unwrap());
+}
+
+#[test]
+fn lenient_collects_row_errors() {
+  let document_text = ARMA_PRESET
+    .replacen("class=\"from-local\"", "class=\"from-mars\"", 1)
+    .replacen("href=\"https://store.steampowered.com/app/1021790\"", "href=\"https://store.steampowered.com/\"", 1);
+  assert!(document_text.parse::<Preset>().is_err());
+
+  let (preset, errors) = Preset::parse_lenient(&document_text);
+  let preset = preset.unwrap();
+  assert_eq!(preset.steam_mods.len(), 2);
+  assert_eq!(preset.local_mods.len(), 1);
+  assert!(preset.dlcs.is_empty());
+  assert!(matches!(&errors[..], [Error::InvalidModRow { index: 1, .. }, Error::InvalidDlcRow { index: 0, .. }]));
+
+  let outcome = PresetParser::new().lenient(true).parse_with_warnings(&document_text).unwrap();
+  assert!(!outcome.is_clean());
+  assert_eq!(outcome.preset, preset);
+  assert_eq!(outcome.errors.len(), 2);
+  assert_eq!(PresetParser::new().lenient(true).parse(&document_text).unwrap(), preset);
+}
+
+#[test]
+fn lenient_still_rejects_non_presets() {
+  let (preset, errors) = Preset::parse_lenient("<html><body><p>hello</p></body></html>");
+  assert!(preset.is_none());
+  assert!(matches!(&errors[..], [Error::NotAPreset]));
+}
+
+#[test]
+fn stats_count_rows() {
+  let (preset, stats) = Preset::parse_with_stats(ARMA_PRESET).unwrap();
+  assert_eq!(preset, ARMA_PRESET.parse().unwrap());
+  assert_eq!((stats.mod_rows, stats.dlc_rows, stats.skipped_rows), (4, 1, 0));
+  assert_eq!(stats.document_bytes, ARMA_PRESET.len());
+
+  let document_text = ARMA_PRESET.replacen("class=\"from-local\"", "class=\"from-mars\"", 1);
+  assert!(PresetParser::new().parse_with_stats(&document_text).is_err());
+  let (preset, stats) = PresetParser::new().lenient(true).parse_with_stats(&document_text).unwrap();
+  assert_eq!(preset.local_mods.len(), 1);
+  assert_eq!((stats.mod_rows, stats.dlc_rows, stats.skipped_rows), (4, 1, 1));
+}
+
+#[test]
+fn custom_steam_hosts() {
+  let mirrored = ARMA_PRESET
+    .replace("https://steamcommunity.com/", "https://workshop.mirror.example/")
+    .replace("https://store.steampowered.com/", "https://store.mirror.example/");
+  let hosts = SteamHosts::new("workshop.mirror.example", "store.mirror.example");
+  let preset = PresetParser::new().steam_hosts(Some(hosts)).parse(&mirrored).unwrap();
+  assert_eq!(preset, ARMA_PRESET.parse::<Preset>().unwrap());
+
+  assert!(matches!(PresetParser::new().parse(&mirrored), Err(Error::InvalidItemLinkSteamWorkshop(..))));
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "streaming")]
+
+use a3_preset_parser::Preset;
+
+const ARMA_PRESET: &str = include_str!("fixtures/arma_preset.html");
+const DAYZ_LIST: &str = include_str!("fixtures/dayz_list.html");
+
+#[test]
+fn streaming_matches_from_str() {
+  for document_text in [ARMA_PRESET, DAYZ_LIST] {
+    assert_eq!(Preset::parse_streaming(document_text).unwrap(), document_text.parse().unwrap());
+  };
+}
+
+#[test]
+fn streaming_ignores_leading_bom() {
+  let document_text = format!("\u{feff}{ARMA_PRESET}");
+  assert_eq!(Preset::parse_streaming(&document_text).unwrap(), ARMA_PRESET.parse().unwrap());
+}
+
+#[test]
+fn streaming_not_a_preset() {
+  assert!(Preset::parse_streaming("<html><body><p>hello</p></body></html>").is_err());
+}
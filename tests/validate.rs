@@ -0,0 +1,54 @@
+use a3_preset_parser::{Game, PresetBuilder, ValidationIssue};
+
+#[test]
+fn valid_preset() {
+  let preset = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA_A3")
+    .local_mod("Tweaks")
+    .dlc(1021790u64, "Contact")
+    .build();
+  assert_eq!(preset.validate(), []);
+}
+
+#[test]
+fn duplicate_ids() {
+  let preset = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "CBA_A3")
+    .steam_mod(463939057u64, "ace")
+    .steam_mod(450814997u64, "CBA")
+    .steam_mod(450814997u64, "CBA_A3")
+    .dlc(1021790u64, "Contact")
+    .dlc(1021790u64, "Contact")
+    .build();
+  assert_eq!(preset.validate(), [
+    ValidationIssue::DuplicateSteamId(450814997u64.into()),
+    ValidationIssue::DuplicateDlcId(1021790u64.into())
+  ]);
+}
+
+#[test]
+fn blank_names() {
+  let preset = PresetBuilder::new(Game::Arma)
+    .steam_mod(450814997u64, "")
+    .steam_mod(463939057u64, " \t")
+    .local_mod("Tweaks")
+    .local_mod("  ")
+    .build();
+  assert_eq!(preset.validate(), [
+    ValidationIssue::BlankSteamModName(450814997u64.into()),
+    ValidationIssue::BlankSteamModName(463939057u64.into()),
+    ValidationIssue::BlankLocalModName(1)
+  ]);
+}
+
+#[test]
+fn local_mod_name_collisions() {
+  let preset = PresetBuilder::new(Game::Arma)
+    .local_mod("Tweaks")
+    .local_mod("TWEAKS ")
+    .local_mod("Tweaks")
+    .build();
+  let issues = preset.validate();
+  assert_eq!(issues, [ValidationIssue::LocalModNameCollision("Tweaks".to_owned(), "TWEAKS ".to_owned())]);
+  assert_eq!(issues[0].to_string(), "local mods \"Tweaks\" and \"TWEAKS \" differ only in case or surrounding whitespace");
+}
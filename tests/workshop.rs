@@ -0,0 +1,23 @@
+use a3_preset_parser::{Game, Preset, PresetKind};
+
+const WORKSHOP_COLLECTION: &str = include_str!("fixtures/workshop_collection.html");
+
+#[test]
+fn workshop_collection() {
+  let preset = Preset::from_workshop_collection_html(WORKSHOP_COLLECTION, Game::Arma).unwrap();
+  assert_eq!(preset.game, Game::Arma);
+  assert_eq!(preset.kind, PresetKind::List);
+  assert_eq!(preset.preset_name.as_deref(), Some("Example Collection"));
+  assert!(preset.local_mods.is_empty() && preset.dlcs.is_empty());
+
+  // The first two items have `sharedfile_` ids, the last only has its link.
+  let steam_mods = preset.steam_mods.iter().map(|m| (m.id.0, m.display_name.as_str())).collect::<Vec<_>>();
+  assert_eq!(steam_mods, [(450814997, "CBA_A3"), (463939057, "ace"), (333310405, "Enhanced Movement")]);
+}
+
+#[test]
+fn workshop_collection_id_takes_precedence_over_link() {
+  let document_text = WORKSHOP_COLLECTION.replacen("id=\"sharedfile_463939057\"", "id=\"sharedfile_843577117\"", 1);
+  let preset = Preset::from_workshop_collection_html(&document_text, Game::Arma).unwrap();
+  assert_eq!(preset.steam_mods[1].id, 843577117);
+}